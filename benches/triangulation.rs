@@ -0,0 +1,70 @@
+//! Compares the incremental triangulations with the divide and conquer one.
+//!
+//! Run with `cargo bench --bench triangulation`, adding `--features parallel` to time the parallel triangulation too.
+
+use std::time::{Duration, Instant};
+
+use meshing::*;
+
+/// Uniform random points in the unit square, from a xorshift generator so that every run uses the same points.
+fn random_points(count: usize) -> Vec<Point> {
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut next = || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        (state >> 11) as f64 / (1u64 << 53) as f64
+    };
+    (0..count).map(|_| build_point(next(), next())).collect()
+}
+
+/// Best time of a few runs.
+fn time<T>(runs: usize, mut run: impl FnMut() -> T) -> Duration {
+    (0..runs)
+        .map(|_| {
+            let start = Instant::now();
+            std::hint::black_box(run());
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn main() {
+    for count in [1_000, 10_000] {
+        let points = random_points(count);
+        let bowyer_watson = TriangulationOptions {
+            kernel: InsertionKernel::BowyerWatson,
+            ..Default::default()
+        };
+
+        println!("{count} points");
+        println!(
+            "  incremental, flips:         {:?}",
+            time(3, || triangulate(&points).unwrap())
+        );
+        println!(
+            "  incremental, Bowyer-Watson: {:?}",
+            time(3, || triangulate_with(&points, &bowyer_watson).unwrap())
+        );
+        println!(
+            "  divide and conquer:         {:?}",
+            time(3, || triangulate_divide_and_conquer(&points).unwrap())
+        );
+    }
+
+    // The incremental triangulations are too slow for these sizes
+    for count in [100_000, 1_000_000] {
+        let points = random_points(count);
+        println!("{count} points");
+        println!(
+            "  divide and conquer:         {:?}",
+            time(3, || triangulate_divide_and_conquer(&points).unwrap())
+        );
+        #[cfg(feature = "parallel")]
+        println!(
+            "  parallel:                   {:?}",
+            time(3, || triangulate_parallel(&points).unwrap())
+        );
+    }
+}
@@ -0,0 +1,304 @@
+//! Adaptation of a triangulation to a solution, from an error indicator given on each cell.
+//!
+//! Cells with a high indicator are refined by inserting a vertex, and interior vertices surrounded by cells with a low
+//! indicator are removed. Both are done on a `Triangulation`, so the mesh stays Delaunay.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::error::*;
+use crate::incremental::*;
+use crate::triangulation::*;
+use crate::types::*;
+use crate::validation::{boundary_vertices, point_key};
+
+/// Parameters of the adaptation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptationOptions {
+    /// Cells whose indicator is above this fraction of the largest indicator are refined.
+    pub refine_fraction: f64,
+    /// Interior vertices whose cells all have an indicator below this fraction of the largest indicator are removed.
+    pub coarsen_fraction: f64,
+    /// No cell is refined once the mesh has this number of cells.
+    pub max_cells: usize,
+    /// An insertion or a removal is undone if it creates a cell of lower quality (see `Cell::quality`),
+    /// unless the cells it replaced were already worse.
+    pub min_quality: f64,
+}
+
+impl Default for AdaptationOptions {
+    fn default() -> Self {
+        AdaptationOptions {
+            refine_fraction: 0.5,
+            coarsen_fraction: 0.05,
+            max_cells: 100_000,
+            min_quality: 0.3,
+        }
+    }
+}
+
+/// Result of `adapt`.
+#[derive(Debug, Clone)]
+pub struct AdaptationOutput {
+    pub triangles: Vec<Triangle>,
+    /// For each new cell, the old cells it comes from, sorted. The new cell lies inside their union,
+    /// and an unchanged cell only comes from itself.
+    pub sources: Vec<Vec<usize>>,
+    /// For each old cell, the new cells coming from it, sorted.
+    pub targets: Vec<Vec<usize>>,
+}
+
+/// What is known of each cell while adapting.
+#[derive(Debug, Clone)]
+struct TrackedCell {
+    vertices: [Point; 3],
+    quality: f64,
+    sources: Vec<usize>,
+}
+
+/// Refines and coarsens a Delaunay triangulation from an indicator given on each cell.
+///
+/// Vertices are removed first, no two neighbors being removed. Cells are then refined from the highest indicator,
+/// by inserting their circumcenter, or their center if the circumcenter is outside of the mesh or gives a bad cell.
+/// A cell already changed by the refinement of another one is not refined again.
+/// The vertices on the boundary are never removed, so the domain is kept.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let vertices: Vec<Point> = (0..=10)
+///     .flat_map(|i| (0..=10).map(move |j| build_point(i as f64 / 10.0, j as f64 / 10.0)))
+///     .collect();
+/// let triangles = triangulate(&vertices).unwrap();
+///
+/// // The solution varies quickly around x = 0.5, and slowly on the sides
+/// let indicator: Vec<f64> = triangles
+///     .iter()
+///     .map(|triangle| (-50.0 * (triangle.center().x - 0.5).powi(2)).exp())
+///     .collect();
+///
+/// let output = adapt(&triangles, &indicator, &AdaptationOptions::default()).unwrap();
+///
+/// let count_near = |triangles: &[Triangle]| {
+///     triangles.iter().filter(|triangle| (triangle.center().x - 0.5).abs() < 0.2).count()
+/// };
+/// assert!(count_near(&output.triangles) > count_near(&triangles));
+/// assert!(output.triangles.len() - count_near(&output.triangles) < triangles.len() - count_near(&triangles));
+/// assert!(validate(&unique_vertices(&output.triangles), &output.triangles, &[]).is_empty());
+///
+/// // Every old cell is covered by new cells, and every new cell comes from old ones
+/// assert!(output.targets.iter().all(|targets| !targets.is_empty()));
+/// assert!(output.sources.iter().all(|sources| !sources.is_empty()));
+/// ```
+pub fn adapt(
+    triangles: &[Triangle],
+    indicator: &[f64],
+    options: &AdaptationOptions,
+) -> Result<AdaptationOutput, MeshError> {
+    if indicator.len() != triangles.len() {
+        return Err(MeshError::FieldSizeMismatch {
+            expected: triangles.len(),
+            found: indicator.len(),
+        });
+    }
+
+    let max_indicator = indicator.iter().copied().fold(0.0, f64::max);
+    let (refine_threshold, coarsen_threshold) = (
+        options.refine_fraction * max_indicator,
+        options.coarsen_fraction * max_indicator,
+    );
+
+    let mut triangulation =
+        build_triangulation(triangles.to_vec(), &TriangulationOptions::default())?;
+    let mut cells: Vec<TrackedCell> = triangles
+        .iter()
+        .enumerate()
+        .map(|(id, triangle)| TrackedCell {
+            vertices: triangle.vertices,
+            quality: triangle.quality(),
+            sources: vec![id],
+        })
+        .collect();
+
+    // Coarsening, on an independent set of the interior vertices
+    let mut blocked: HashSet<(u64, u64)> = HashSet::new();
+    for (vertex, (star, on_boundary)) in vertex_cells(triangles) {
+        if on_boundary
+            || blocked.contains(&point_key(&vertex))
+            || star.iter().any(|id| indicator[*id] >= coarsen_threshold)
+        {
+            continue;
+        }
+        let Some(id) = triangulation.vertex_id(&vertex) else {
+            continue;
+        };
+
+        let changes = triangulation.remove_point(id)?;
+        let qualities = apply_changes(&mut cells, &changes, triangulation.triangles(), triangles);
+        if is_worse(qualities, options.min_quality) {
+            let (_, changes) = triangulation.insert_point(&vertex)?;
+            apply_changes(&mut cells, &changes, triangulation.triangles(), triangles);
+            continue;
+        }
+
+        for id in star {
+            blocked.extend(triangles[id].vertices.iter().map(point_key));
+        }
+    }
+
+    // Refinement, from the highest indicator
+    let mut refined: Vec<usize> = (0..triangles.len())
+        .filter(|id| indicator[*id] > refine_threshold)
+        .collect();
+    refined.sort_by(|a, b| indicator[*b].total_cmp(&indicator[*a]));
+
+    for old_id in refined {
+        if triangulation.triangles().len() >= options.max_cells {
+            break;
+        }
+        // The cell must not have been changed since the start
+        let triangle = triangles[old_id];
+        if !matches!(triangulation.locate(&triangle.center()),
+            Ok(id) if cells[id].sources == [old_id] && cells[id].vertices == triangle.vertices)
+        {
+            continue;
+        }
+
+        for point in [triangle.circumcenter(), triangle.center()] {
+            let (vertex, changes) = match triangulation.insert_point(&point) {
+                Ok((_, changes)) if changes.created.is_empty() => continue,
+                Ok(inserted) => inserted,
+                Err(MeshError::PointOutsideDomain { .. } | MeshError::DuplicatePoint { .. }) => {
+                    continue
+                }
+                Err(error) => return Err(error),
+            };
+            let qualities =
+                apply_changes(&mut cells, &changes, triangulation.triangles(), triangles);
+
+            if !is_worse(qualities, options.min_quality) {
+                break;
+            }
+            let changes = triangulation.remove_point(vertex)?;
+            apply_changes(&mut cells, &changes, triangulation.triangles(), triangles);
+        }
+    }
+
+    let sources: Vec<Vec<usize>> = cells.into_iter().map(|cell| cell.sources).collect();
+    let mut targets = vec![Vec::new(); triangles.len()];
+    for (new_id, sources) in sources.iter().enumerate() {
+        for old_id in sources {
+            targets[*old_id].push(new_id);
+        }
+    }
+
+    Ok(AdaptationOutput {
+        triangles: triangulation.triangles().to_vec(),
+        sources,
+        targets,
+    })
+}
+
+/// Lists each vertex with the cells around it, and whether it is on the boundary.
+fn vertex_cells(triangles: &[Triangle]) -> Vec<(Point, (Vec<usize>, bool))> {
+    let boundary = boundary_vertices(triangles);
+    let mut ids: HashMap<(u64, u64), usize> = HashMap::new();
+    let mut vertices: Vec<(Point, (Vec<usize>, bool))> = Vec::new();
+
+    for (id, triangle) in triangles.iter().enumerate() {
+        for vertex in &triangle.vertices {
+            let key = point_key(vertex);
+            let vertex_id = *ids.entry(key).or_insert(vertices.len());
+            if vertex_id == vertices.len() {
+                vertices.push((*vertex, (Vec::new(), boundary.contains(&key))));
+            }
+            vertices[vertex_id].1 .0.push(id);
+        }
+    }
+
+    vertices
+}
+
+/// Updates the tracked cells after a modification of the triangulation.
+/// A new cell identical to a deleted one takes its place. Otherwise it comes from the sources of the deleted cells
+/// whose old cell has a bounding box overlapping its own.
+///
+/// Returns the worst quality of the deleted cells and of the created ones.
+fn apply_changes(
+    cells: &mut Vec<TrackedCell>,
+    changes: &ChangeSet,
+    triangles: &[Triangle],
+    old_triangles: &[Triangle],
+) -> (f64, f64) {
+    let deleted: Vec<TrackedCell> = changes
+        .deleted
+        .iter()
+        .map(|id| cells[*id].clone())
+        .collect();
+    let mut sources: Vec<usize> = deleted
+        .iter()
+        .flat_map(|cell| cell.sources.iter().copied())
+        .collect();
+    sources.sort_unstable();
+    sources.dedup();
+
+    let moved: Vec<TrackedCell> = changes
+        .moved
+        .iter()
+        .map(|(from, _)| cells[*from].clone())
+        .collect();
+    for ((_, to), cell) in changes.moved.iter().zip(moved) {
+        cells[*to] = cell;
+    }
+
+    // Every index past the end is a created cell
+    cells.resize_with(triangles.len(), || TrackedCell {
+        vertices: [build_point(0.0, 0.0); 3],
+        quality: 0.0,
+        sources: Vec::new(),
+    });
+    for &id in &changes.created {
+        let triangle = &triangles[id];
+        cells[id] = match deleted
+            .iter()
+            .find(|cell| cell.vertices == triangle.vertices)
+        {
+            Some(cell) => cell.clone(),
+            None => TrackedCell {
+                vertices: triangle.vertices,
+                quality: triangle.quality(),
+                sources: sources
+                    .iter()
+                    .copied()
+                    .filter(|old_id| {
+                        let (min_1, max_1) = triangle_box(&old_triangles[*old_id]);
+                        let (min_2, max_2) = triangle_box(triangle);
+                        boxes_overlap((&min_1, &max_1), (&min_2, &max_2))
+                    })
+                    .collect(),
+            },
+        };
+    }
+
+    (
+        deleted
+            .iter()
+            .map(|cell| cell.quality)
+            .fold(f64::MAX, f64::min),
+        changes
+            .created
+            .iter()
+            .map(|id| cells[*id].quality)
+            .fold(f64::MAX, f64::min),
+    )
+}
+
+/// Checks if a modification created a cell under the minimum quality, worse than the cells it replaced.
+fn is_worse((deleted, created): (f64, f64), min_quality: f64) -> bool {
+    created < min_quality && created < deleted
+}
+
+fn triangle_box(triangle: &Triangle) -> (Point, Point) {
+    bounding_box(&triangle.vertices).unwrap_or((triangle.vertices[0], triangle.vertices[0]))
+}
@@ -0,0 +1,342 @@
+//! Airfoil sections, read from coordinate files or generated from the NACA formulas.
+//!
+//! An airfoil is a closed curve of unit chord, going from the trailing edge along the upper surface
+//! to the leading edge, and back along the lower surface (Selig ordering, counter-clockwise).
+
+use std::f64::consts::PI;
+use std::path::Path;
+
+use crate::error::*;
+use crate::geometry::*;
+use crate::types::*;
+
+/// Section of a wing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Airfoil {
+    pub name: String,
+    /// Points from the trailing edge of the upper surface to the trailing edge of the lower surface.
+    pub points: Vec<Point>,
+}
+
+/// Outer boundary of the domain around an airfoil.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Farfield {
+    Box { min: Point, max: Point },
+    Circle { center: Point, radius: f64 },
+}
+
+impl Airfoil {
+    /// Gives the closed boundary of the airfoil, ending on its first point.
+    /// A blunt trailing edge is closed by a segment.
+    pub fn boundary(&self) -> Vec<Point> {
+        let mut boundary = self.points.clone();
+        if boundary.first() != boundary.last() {
+            boundary.push(self.points[0]);
+        }
+        boundary
+    }
+
+    /// Redistributes the points of the boundary following a size function,
+    /// with more points around the leading edge (see `resample_with`). The trailing edge is kept.
+    pub fn resample<F: Fn(&Point) -> f64>(&self, size: F) -> Vec<Point> {
+        resample_with(&self.boundary(), size, &ResamplingOptions::default())
+    }
+}
+
+/// Reads an airfoil file, in the Selig or the Lednicer format (see `parse_airfoil`).
+///
+/// Fails with `MeshError::Io` if the file cannot be read.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// assert!(matches!(
+///     read_airfoil("missing/naca0012.dat"),
+///     Err(MeshError::Io { kind: std::io::ErrorKind::NotFound, .. })
+/// ));
+/// ```
+pub fn read_airfoil<P: AsRef<Path>>(path: P) -> Result<Airfoil, MeshError> {
+    let text = std::fs::read_to_string(path).map_err(|error| MeshError::Io {
+        kind: error.kind(),
+        message: error.to_string(),
+    })?;
+    parse_airfoil(&text)
+}
+
+/// Reads the coordinates of an airfoil, in the Selig or the Lednicer format.
+///
+/// Both start with the name of the airfoil. A Selig file then lists the points from the trailing edge
+/// along the upper surface and back along the lower surface. A Lednicer file gives the number of points
+/// of each surface, then the upper and the lower surfaces, both from the leading edge to the trailing edge.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let selig = "Test airfoil\n1.0 0.0\n0.5 0.1\n0.0 0.0\n0.5 -0.05\n1.0 0.0\n";
+/// let lednicer = "Test airfoil\n3. 3.\n\n0.0 0.0\n0.5 0.1\n1.0 0.0\n\n0.0 0.0\n0.5 -0.05\n1.0 0.0\n";
+///
+/// let airfoil = parse_airfoil(selig).unwrap();
+/// assert_eq!(airfoil.name, "Test airfoil");
+/// assert_eq!(airfoil.points.len(), 5);
+/// assert_eq!(parse_airfoil(lednicer).unwrap(), airfoil);
+///
+/// assert_eq!(
+///     parse_airfoil("Test airfoil\n1.0 0.0\n0.5\n"),
+///     Err(MeshError::ParseError { line: 3, reason: "expected two coordinates" })
+/// );
+/// ```
+pub fn parse_airfoil(text: &str) -> Result<Airfoil, MeshError> {
+    let mut name = String::new();
+    // Coordinates with their line number
+    let mut rows: Vec<(usize, f64, f64)> = Vec::new();
+
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let values: Vec<&str> = line.split_whitespace().collect();
+        if values.is_empty() {
+            continue;
+        }
+
+        let numbers: Result<Vec<f64>, _> = values.iter().map(|value| value.parse()).collect();
+        match numbers {
+            Err(_) if rows.is_empty() && name.is_empty() => name = line.trim().to_string(),
+            Ok(numbers) if numbers.len() == 2 => rows.push((line_number, numbers[0], numbers[1])),
+            _ => {
+                return Err(MeshError::ParseError {
+                    line: line_number,
+                    reason: "expected two coordinates",
+                })
+            }
+        }
+    }
+
+    let points: Vec<Point> = match rows.first() {
+        None => {
+            return Err(MeshError::ParseError {
+                line: 0,
+                reason: "no coordinates",
+            })
+        }
+        // Coordinates are normalised by the chord, so larger values are the sizes of the Lednicer surfaces
+        Some(&(line, upper, lower)) if upper > 1.5 && lower > 1.5 => {
+            let (upper, lower) = (upper as usize, lower as usize);
+            if rows.len() != 1 + upper + lower {
+                return Err(MeshError::ParseError {
+                    line,
+                    reason: "number of points does not match the file",
+                });
+            }
+
+            let point = |&(_, x, y): &(usize, f64, f64)| build_point(x, y);
+            let mut points: Vec<Point> = rows[1..=upper].iter().rev().map(point).collect();
+            let lower_surface = &rows[1 + upper..];
+            // The leading edge is usually on both surfaces
+            let skip = usize::from(point(&lower_surface[0]) == points[points.len() - 1]);
+            points.extend(lower_surface.iter().skip(skip).map(point));
+            points
+        }
+        Some(_) => rows.iter().map(|&(_, x, y)| build_point(x, y)).collect(),
+    };
+
+    if points.len() < 3 {
+        return Err(MeshError::ParseError {
+            line: 0,
+            reason: "not enough points",
+        });
+    }
+
+    Ok(Airfoil { name, points })
+}
+
+/// Generates a NACA 4-digit or 5-digit airfoil with a closed trailing edge.
+///
+/// The points are clustered at the leading and trailing edges with a cosine distribution,
+/// with `points_per_side` points on each surface.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let naca0012 = naca("0012", 50).unwrap();
+/// let thickness = naca0012
+///     .points
+///     .iter()
+///     .map(|point| point.y)
+///     .fold(0.0, f64::max);
+///
+/// assert_eq!(naca0012.points.len(), 99);
+/// assert!((thickness - 0.06).abs() < 1e-3);
+///
+/// // The closed boundary of a cambered airfoil is counter-clockwise
+/// let naca23012 = naca("23012", 50).unwrap();
+/// let polygon = build_polygon(naca23012.points.clone(), vec![None; 99]);
+/// assert!(polygon.signed_area() > 0.0);
+///
+/// assert!(naca("123", 50).is_err());
+/// ```
+pub fn naca(code: &str, points_per_side: usize) -> Result<Airfoil, MeshError> {
+    let invalid = MeshError::ParseError {
+        line: 0,
+        reason: "invalid NACA code",
+    };
+    let digits: Vec<u32> = code
+        .chars()
+        .map(|digit| digit.to_digit(10))
+        .collect::<Option<_>>()
+        .ok_or(invalid.clone())?;
+    let thickness = match digits.len() {
+        4 | 5 => (10 * digits[digits.len() - 2] + digits[digits.len() - 1]) as f64 / 100.0,
+        _ => return Err(invalid),
+    };
+
+    let camber = if digits.len() == 4 {
+        naca_four_digit_camber(digits[0] as f64 / 100.0, digits[1] as f64 / 10.0)
+    } else {
+        naca_five_digit_camber(digits[0], digits[1], digits[2]).ok_or(invalid)?
+    };
+
+    let half_thickness = |x: f64| {
+        5.0 * thickness
+            * (0.2969 * x.sqrt() - 0.1260 * x - 0.3516 * x.powi(2) + 0.2843 * x.powi(3)
+                - 0.1036 * x.powi(4))
+    };
+
+    let count = points_per_side.max(2);
+    let surface_point = |i: usize, side: f64| {
+        let x = 0.5 * (1.0 - (PI * i as f64 / (count - 1) as f64).cos());
+        let (y, slope) = camber(x);
+        let angle = slope.atan();
+        build_point(
+            x - side * half_thickness(x) * angle.sin(),
+            y + side * half_thickness(x) * angle.cos(),
+        )
+    };
+
+    // Upper surface from the trailing edge, then lower surface without the leading edge
+    let mut points: Vec<Point> = (0..count).rev().map(|i| surface_point(i, 1.0)).collect();
+    points.extend((1..count).map(|i| surface_point(i, -1.0)));
+    // Both surfaces end on the same trailing edge, up to rounding errors
+    let last = points.len() - 1;
+    points[last] = points[0];
+
+    Ok(Airfoil {
+        name: format!("NACA {}", code),
+        points,
+    })
+}
+
+/// Height and slope of the mean line of an airfoil, along the chord.
+type MeanLine = Box<dyn Fn(f64) -> (f64, f64)>;
+
+/// Mean line of a NACA 4-digit airfoil, giving its height and slope.
+fn naca_four_digit_camber(camber: f64, position: f64) -> MeanLine {
+    Box::new(move |x: f64| {
+        if camber == 0.0 || position == 0.0 {
+            (0.0, 0.0)
+        } else if x < position {
+            (
+                camber / position.powi(2) * (2.0 * position * x - x * x),
+                2.0 * camber / position.powi(2) * (position - x),
+            )
+        } else {
+            (
+                camber / (1.0 - position).powi(2)
+                    * (1.0 - 2.0 * position + 2.0 * position * x - x * x),
+                2.0 * camber / (1.0 - position).powi(2) * (position - x),
+            )
+        }
+    })
+}
+
+/// Mean line of a NACA 5-digit airfoil, giving its height and slope.
+/// Only the standard positions of the maximum camber are defined.
+// The tabulated 0.3180 is not an approximation of 1 / pi
+#[allow(clippy::approx_constant)]
+fn naca_five_digit_camber(lift: u32, position: u32, reflex: u32) -> Option<MeanLine> {
+    // Parameters (m, k1, k2 / k1) of the mean lines, for a design lift coefficient of 0.3
+    let (m, k1, ratio) = match (position, reflex) {
+        (1, 0) => (0.0580, 361.4, 0.0),
+        (2, 0) => (0.1260, 51.64, 0.0),
+        (3, 0) => (0.2025, 15.957, 0.0),
+        (4, 0) => (0.2900, 6.643, 0.0),
+        (5, 0) => (0.3910, 3.230, 0.0),
+        (2, 1) => (0.1300, 51.990, 0.000764),
+        (3, 1) => (0.2170, 15.793, 0.00677),
+        (4, 1) => (0.3180, 6.520, 0.0303),
+        (5, 1) => (0.4410, 3.191, 0.1355),
+        _ => return None,
+    };
+    // The mean line scales with the design lift coefficient, which is 0.15 times the first digit
+    let k1 = k1 * 0.15 * lift as f64 / 0.3;
+
+    Some(Box::new(move |x: f64| {
+        if reflex == 0 {
+            if x < m {
+                (
+                    k1 / 6.0 * (x.powi(3) - 3.0 * m * x * x + m * m * (3.0 - m) * x),
+                    k1 / 6.0 * (3.0 * x * x - 6.0 * m * x + m * m * (3.0 - m)),
+                )
+            } else {
+                (k1 / 6.0 * m.powi(3) * (1.0 - x), -k1 / 6.0 * m.powi(3))
+            }
+        } else {
+            let cubic = if x < m { 1.0 } else { ratio };
+            (
+                k1 / 6.0
+                    * (cubic * (x - m).powi(3) - ratio * (1.0 - m).powi(3) * x - m.powi(3) * x
+                        + m.powi(3)),
+                k1 / 6.0 * (3.0 * cubic * (x - m).powi(2) - ratio * (1.0 - m).powi(3) - m.powi(3)),
+            )
+        }
+    }))
+}
+
+/// Builds the PSLG of the domain between an airfoil boundary and a farfield,
+/// tagged `airfoil` and `farfield`. The farfield is discretised with the target size `farfield_size`.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let airfoil = naca("2412", 40).unwrap();
+/// let boundary = airfoil.resample(|point| 0.01 + 0.05 * (point.x - 0.5).abs());
+/// let farfield = Farfield::Box {
+///     min: build_point(-1.0, -1.0),
+///     max: build_point(2.0, 1.0),
+/// };
+///
+/// let pslg = airfoil_pslg(&boundary, &farfield, 0.25);
+/// let mesh = triangulate_pslg(&pslg, &[]).unwrap();
+///
+/// let area: f64 = mesh.triangles.iter().map(|triangle| triangle.signed_area()).sum();
+/// let airfoil_area = build_polygon(boundary.clone(), vec![None; boundary.len()]).signed_area();
+/// assert!((area - (6.0 - airfoil_area)).abs() < 1e-9);
+/// ```
+pub fn airfoil_pslg(boundary: &[Point], farfield: &Farfield, farfield_size: f64) -> Pslg {
+    let builder = PslgBuilder::new(farfield_size);
+    let builder = match *farfield {
+        Farfield::Box { min, max } => builder.polyline(
+            vec![
+                min,
+                build_point(max.x, min.y),
+                max,
+                build_point(min.x, max.y),
+                min,
+            ],
+            "farfield",
+        ),
+        Farfield::Circle { center, radius } => builder.circle(center, radius, "farfield"),
+    };
+    let mut pslg = builder.build();
+
+    let tolerance = 1e-9 * polyline_length(boundary);
+    pslg.add_chain(boundary, "airfoil", tolerance);
+
+    pslg
+}
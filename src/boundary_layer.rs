@@ -0,0 +1,735 @@
+//! Anisotropic layers of cells along walls, to resolve viscous boundary layers.
+//!
+//! The walls are the segments of a PSLG with given tags, joined end to end into open or closed chains.
+//! The layers are extruded from them along the vertex normals, then stitched to an isotropic
+//! Delaunay triangulation of the rest of the domain.
+
+use std::collections::{HashMap, HashSet};
+use std::f64::consts::PI;
+
+use crate::cell_tree::*;
+use crate::error::*;
+use crate::geometry::*;
+use crate::mixed_mesh::*;
+use crate::structured::BoundaryFace;
+use crate::types::*;
+use crate::validation::{edge_key, point_key};
+
+/// Largest stretching of the extrusion directions, at concave corners and along the segments ending the layers.
+const MAX_STRETCHING: f64 = 2.0;
+
+/// Parameters of the extrusion of the layers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundaryLayerOptions {
+    /// Height of the cells touching the wall.
+    pub first_height: f64,
+    /// Ratio between the heights of two successive layers.
+    pub growth_ratio: f64,
+    /// Number of layers, fewer are built if `max_thickness` is reached first.
+    pub layers: usize,
+    pub max_thickness: f64,
+    /// At convex corners, the normals are spread in a fan with at most this angle (in radians) between them.
+    pub fan_angle: f64,
+    /// Splits each quad of the layers into two triangles.
+    pub split_into_triangles: bool,
+}
+
+impl Default for BoundaryLayerOptions {
+    fn default() -> Self {
+        BoundaryLayerOptions {
+            first_height: 1e-3,
+            growth_ratio: 1.2,
+            layers: 10,
+            max_thickness: f64::INFINITY,
+            fan_angle: PI / 6.0,
+            split_into_triangles: false,
+        }
+    }
+}
+
+/// Cells extruded from walls.
+#[derive(Debug, Clone, Default)]
+pub struct BoundaryLayer {
+    pub cells: Vec<MeshCell>,
+    /// For each cell, the tag of the wall segment it is extruded from.
+    pub walls: Vec<String>,
+    /// Outer boundary of the layers of each chain of wall segments, in the same direction as the wall.
+    /// The front of a closed chain ends on its first point, and the front of an open chain goes from its first point
+    /// to its last one along the sides of the layers.
+    pub fronts: Vec<Vec<Point>>,
+}
+
+/// Mesh of a domain with boundary layers along its walls.
+#[derive(Debug, Clone, Default)]
+pub struct BoundaryLayerMesh {
+    pub mesh: MixedMesh,
+    /// For each cell, the tag of the wall it is extruded from, or `None` for the cells of the triangulation.
+    pub walls: Vec<Option<String>>,
+    pub boundaries: Vec<BoundaryFace>,
+}
+
+/// Wall segments of a PSLG joined end to end.
+struct Chain {
+    /// Indices of the points in the PSLG, the first one not being repeated at the end of a closed chain.
+    points: Vec<usize>,
+    /// Tag of each segment, from the i-th point to the next one.
+    tags: Vec<String>,
+    closed: bool,
+    /// How the layers end at the first and at the last point of an open chain, `ChainEnd::Normal` for a closed one.
+    ends: [ChainEnd; 2],
+}
+
+/// How the layers end at an end of an open chain.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ChainEnd {
+    /// No other segment ends there, the wall then having fluid on both sides.
+    Free,
+    /// Along the normal of the wall, the other segments ending there being too far from it.
+    Normal,
+    /// Along this other segment of the PSLG.
+    Following(usize),
+}
+
+/// Front of the layers of a chain, see `BoundaryLayer::fronts`.
+struct ChainFront {
+    points: Vec<Point>,
+    /// Number of edges of the front along the first and the last side of the layers of an open chain.
+    sides: [usize; 2],
+}
+
+/// Direction along which the points of the layers are placed from a wall vertex.
+struct Ray {
+    origin: Point,
+    direction: Vector,
+    /// Largest distance along the direction of the points of the layers.
+    max_distance: f64,
+    /// Index in the chain of the segment extruded between this ray and the next one.
+    segment: usize,
+}
+
+/// Extrudes layers of cells from the segments of a PSLG tagged with one of the `walls`, the fluid being on the right
+/// of each segment, so that a body is given counter-clockwise.
+///
+/// The wall segments are joined into open or closed chains, and each vertex is extruded along the average normal
+/// of its edges, or along a fan of normals at convex corners sharper than `fan_angle`.
+/// The layers of an open chain end along the closest other segment, or along the normal if there is none.
+/// Layers are made thinner at concave corners so that no cell is inverted.
+///
+/// Fails with `MeshError::EmptyInput` if there is no wall segment, and `MeshError::DegenerateInput` if the walls branch.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let square = PslgBuilder::new(1.0)
+///     .polyline(
+///         vec![
+///             build_point(0.0, 0.0),
+///             build_point(1.0, 0.0),
+///             build_point(1.0, 1.0),
+///             build_point(0.0, 1.0),
+///             build_point(0.0, 0.0),
+///         ],
+///         "wall",
+///     )
+///     .build();
+/// let options = BoundaryLayerOptions { first_height: 0.01, layers: 3, ..Default::default() };
+/// let layer = extrude_boundary_layer(&square, &["wall"], &options).unwrap();
+///
+/// // Each corner has a fan of 4 normals, giving 3 more cells per layer
+/// assert_eq!(layer.cells.len(), 3 * (4 + 4 * 3));
+/// assert!(layer.cells.iter().all(|cell| cell.signed_area() > 0.0));
+/// ```
+pub fn extrude_boundary_layer(
+    pslg: &Pslg,
+    walls: &[&str],
+    options: &BoundaryLayerOptions,
+) -> Result<BoundaryLayer, MeshError> {
+    let chains = wall_chains(pslg, walls)?;
+    Ok(extrude_chains(pslg, &chains, options).0)
+}
+
+/// Joins the wall segments of a PSLG into chains, open chains first.
+fn wall_chains(pslg: &Pslg, walls: &[&str]) -> Result<Vec<Chain>, MeshError> {
+    let is_wall = |segment: &Segment| walls.contains(&segment.tag.as_str());
+
+    // Wall segment starting at each point
+    let mut next: HashMap<usize, usize> = HashMap::new();
+    let mut ends = HashSet::new();
+    for (id, segment) in pslg.segments.iter().enumerate() {
+        if is_wall(segment)
+            && (next.insert(segment.start, id).is_some() || !ends.insert(segment.end))
+        {
+            return Err(MeshError::DegenerateInput);
+        }
+    }
+    if next.is_empty() {
+        return Err(MeshError::EmptyInput);
+    }
+
+    let mut starts: Vec<usize> = next.keys().copied().collect();
+    starts.sort_unstable();
+    starts.sort_by_key(|start| ends.contains(start));
+
+    let mut visited = HashSet::new();
+    let mut chains = Vec::new();
+    for start in starts {
+        if visited.contains(&next[&start]) {
+            continue;
+        }
+
+        let mut chain = Chain {
+            points: vec![start],
+            tags: Vec::new(),
+            closed: false,
+            ends: [ChainEnd::Normal; 2],
+        };
+        let mut current = start;
+        while let Some(&id) = next.get(&current) {
+            visited.insert(id);
+            chain.tags.push(pslg.segments[id].tag.clone());
+            current = pslg.segments[id].end;
+            if current == start {
+                chain.closed = true;
+                break;
+            }
+            chain.points.push(current);
+        }
+
+        if chain.closed && chain.points.len() < 3 {
+            return Err(MeshError::DegenerateInput);
+        }
+        chains.push(chain);
+    }
+
+    // The layers of an open chain end along the other segment ending there which is the closest to the normal
+    let chain_end = |vertex: usize, normal: Vector| {
+        let mut end = ChainEnd::Free;
+        let mut best = 1.0 / MAX_STRETCHING;
+        for (id, segment) in pslg.segments.iter().enumerate() {
+            let other = match segment {
+                _ if is_wall(segment) => continue,
+                Segment { start, end, .. } if *start == vertex => *end,
+                Segment { start, end, .. } if *end == vertex => *start,
+                _ => continue,
+            };
+            let alignment = &normal
+                * &pslg.points[vertex]
+                    .segment_to(&pslg.points[other])
+                    .normalize();
+
+            if end == ChainEnd::Free {
+                end = ChainEnd::Normal;
+            }
+            if alignment >= best {
+                best = alignment;
+                end = ChainEnd::Following(id);
+            }
+        }
+        end
+    };
+    let normal = |start: usize, end: usize| {
+        pslg.points[start]
+            .segment_to(&pslg.points[end])
+            .orthogonal_vector()
+            .normalize()
+    };
+    for chain in chains.iter_mut().filter(|chain| !chain.closed) {
+        let last = chain.points.len() - 1;
+        chain.ends = [
+            chain_end(chain.points[0], normal(chain.points[0], chain.points[1])),
+            chain_end(
+                chain.points[last],
+                normal(chain.points[last - 1], chain.points[last]),
+            ),
+        ];
+    }
+
+    Ok(chains)
+}
+
+/// Extrudes the layers of each chain.
+fn extrude_chains(
+    pslg: &Pslg,
+    chains: &[Chain],
+    options: &BoundaryLayerOptions,
+) -> (BoundaryLayer, Vec<ChainFront>) {
+    let mut distances = vec![0.0];
+    let mut height = options.first_height;
+    while distances.len() <= options.layers {
+        let distance = distances[distances.len() - 1] + height;
+        if distance > options.max_thickness {
+            break;
+        }
+        distances.push(distance);
+        height *= options.growth_ratio;
+    }
+
+    let mut layer = BoundaryLayer::default();
+    let mut fronts = Vec::new();
+
+    for chain in chains {
+        let rays = chain_rays(pslg, chain, options.fan_angle);
+        let last = rays.len() - 1;
+        let mut gaps: Vec<(usize, usize)> = (0..last).map(|i| (i, i + 1)).collect();
+        if chain.closed {
+            gaps.push((last, 0));
+        }
+
+        let counts = layer_counts(&rays, &gaps, &distances, options.split_into_triangles);
+        for &(first, second) in &gaps {
+            for (_, cell) in gap_cells(
+                [&rays[first], &rays[second]],
+                [counts[first], counts[second]],
+                &distances,
+                options.split_into_triangles,
+            ) {
+                layer.cells.push(cell);
+                layer.walls.push(chain.tags[rays[first].segment].clone());
+            }
+        }
+
+        let point = |ray: &Ray, layer: usize| ray_point(ray, distances[layer]);
+        let mut front = ChainFront {
+            points: Vec::new(),
+            sides: [0, 0],
+        };
+        if chain.closed {
+            front.points.extend(
+                rays.iter()
+                    .zip(&counts)
+                    .map(|(ray, count)| point(ray, *count)),
+            );
+            front.points.push(point(&rays[0], counts[0]));
+        } else {
+            front.sides = [counts[0], counts[last]];
+            front
+                .points
+                .extend((0..counts[0]).map(|layer| point(&rays[0], layer)));
+            front.points.extend(
+                rays.iter()
+                    .zip(&counts)
+                    .map(|(ray, count)| point(ray, *count)),
+            );
+            front.points.extend(
+                (0..counts[last])
+                    .rev()
+                    .map(|layer| point(&rays[last], layer)),
+            );
+        }
+        // The rays of a fan without layers all give the wall vertex
+        front.points.dedup();
+
+        layer.fronts.push(front.points.clone());
+        fronts.push(front);
+    }
+
+    (layer, fronts)
+}
+
+/// Computes the extrusion directions of each vertex of a chain.
+fn chain_rays(pslg: &Pslg, chain: &Chain, fan_angle: f64) -> Vec<Ray> {
+    let count = chain.points.len();
+    let point = |i: usize| pslg.points[chain.points[i % count]];
+    let edge_normal = |i: usize| {
+        point(i)
+            .segment_to(&point(i + 1))
+            .orthogonal_vector()
+            .normalize()
+    };
+
+    let mut rays = Vec::new();
+
+    for i in 0..count {
+        let vertex = point(i);
+
+        if !chain.closed && (i == 0 || i == count - 1) {
+            let (normal, end) = if i == 0 {
+                (edge_normal(0), chain.ends[0])
+            } else {
+                (edge_normal(count - 2), chain.ends[1])
+            };
+            let (direction, max_distance) = match end {
+                ChainEnd::Following(id) => {
+                    // Stretched so that the layers keep their height, and stopped at half of the segment
+                    let segment = &pslg.segments[id];
+                    let other = if segment.start == chain.points[i] {
+                        segment.end
+                    } else {
+                        segment.start
+                    };
+                    let along = vertex.segment_to(&pslg.points[other]);
+                    let stretching = along.norm() / (&normal * &along);
+                    (
+                        &along.normalize() * stretching,
+                        0.5 * along.norm() / stretching,
+                    )
+                }
+                ChainEnd::Free | ChainEnd::Normal => (normal, f64::INFINITY),
+            };
+            rays.push(Ray {
+                origin: vertex,
+                direction,
+                max_distance,
+                segment: i,
+            });
+            continue;
+        }
+
+        let (previous, next) = (edge_normal(i + count - 1), edge_normal(i));
+        let cross = previous.x * next.y - previous.y * next.x;
+        let angle = cross.atan2(&previous * &next);
+
+        if angle > fan_angle {
+            // Convex corner, the normals are rotated from the previous edge normal to the next one
+            let steps = (angle / fan_angle).ceil() as usize;
+            for step in 0..=steps {
+                let rotation = angle * step as f64 / steps as f64;
+                rays.push(Ray {
+                    origin: vertex,
+                    direction: build_vector(
+                        previous.x * rotation.cos() - previous.y * rotation.sin(),
+                        previous.x * rotation.sin() + previous.y * rotation.cos(),
+                    ),
+                    max_distance: f64::INFINITY,
+                    segment: if step < steps {
+                        (i + count - 1) % count
+                    } else {
+                        i
+                    },
+                });
+            }
+        } else {
+            // The stretching is limited at concave corners so that the layers do not fold too fast
+            let stretching = (1.0 / (angle / 2.0).cos()).min(MAX_STRETCHING);
+            rays.push(Ray {
+                origin: vertex,
+                direction: &(&previous + &next).normalize() * stretching,
+                max_distance: f64::INFINITY,
+                segment: i,
+            });
+        }
+    }
+
+    // Rays getting closer, as at concave corners, stop before half of the distance at which they cross
+    for i in 0..rays.len() - usize::from(!chain.closed) {
+        let (first, second) = (&rays[i], &rays[(i + 1) % rays.len()]);
+        let (to_second, denominator) = (
+            first.origin.segment_to(&second.origin),
+            first.direction.x * second.direction.y - first.direction.y * second.direction.x,
+        );
+        if first.origin == second.origin || denominator == 0.0 {
+            continue;
+        }
+        let along_first =
+            (to_second.x * second.direction.y - to_second.y * second.direction.x) / denominator;
+        let along_second =
+            (to_second.x * first.direction.y - to_second.y * first.direction.x) / denominator;
+
+        if along_first > 0.0 && along_second > 0.0 {
+            rays[i].max_distance = rays[i].max_distance.min(0.5 * along_first);
+            let next = (i + 1) % rays.len();
+            rays[next].max_distance = rays[next].max_distance.min(0.5 * along_second);
+        }
+    }
+
+    rays
+}
+
+/// Gives the number of layers along each ray.
+fn layer_counts(
+    rays: &[Ray],
+    gaps: &[(usize, usize)],
+    distances: &[f64],
+    split_into_triangles: bool,
+) -> Vec<usize> {
+    let mut counts: Vec<usize> = rays
+        .iter()
+        .map(|ray| {
+            distances
+                .iter()
+                .filter(|distance| **distance <= ray.max_distance)
+                .count()
+                - 1
+        })
+        .collect();
+
+    loop {
+        // Neighbor rays differ by at most one layer, and the rays of a fan have the same number of layers
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &(first, second) in gaps {
+                let difference = usize::from(rays[first].origin != rays[second].origin);
+                for (long, short) in [(first, second), (second, first)] {
+                    if counts[long] > counts[short] + difference {
+                        counts[long] = counts[short] + difference;
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        let inverted = gaps.iter().find_map(|&(first, second)| {
+            gap_cells(
+                [&rays[first], &rays[second]],
+                [counts[first], counts[second]],
+                distances,
+                split_into_triangles,
+            )
+            .into_iter()
+            .find(|(_, cell)| cell.signed_area() <= 0.0)
+            .map(|(layer, _)| (first, second, layer))
+        });
+        match inverted {
+            Some((first, second, layer)) => {
+                counts[first] = counts[first].min(layer);
+                counts[second] = counts[second].min(layer);
+            }
+            None => return counts,
+        }
+    }
+}
+
+/// Builds the cells between two consecutive rays, each with its layer.
+fn gap_cells(
+    [first, second]: [&Ray; 2],
+    counts: [usize; 2],
+    distances: &[f64],
+    split_into_triangles: bool,
+) -> Vec<(usize, MeshCell)> {
+    let point = |ray: &Ray, layer: usize| ray_point(ray, distances[layer]);
+    let common = counts[0].min(counts[1]);
+    let mut cells: Vec<(usize, MeshCell)> = Vec::new();
+
+    for layer in 0..common {
+        let (inner, outer) = (point(first, layer), point(first, layer + 1));
+        let (next_inner, next_outer) = (point(second, layer), point(second, layer + 1));
+
+        if inner == next_inner {
+            cells.push((
+                layer,
+                build_triangle([inner, outer, next_outer], [None; 3]).into(),
+            ));
+        } else if split_into_triangles {
+            cells.push((
+                layer,
+                build_triangle([inner, outer, next_outer], [None; 3]).into(),
+            ));
+            cells.push((
+                layer,
+                build_triangle([inner, next_outer, next_inner], [None; 3]).into(),
+            ));
+        } else {
+            cells.push((
+                layer,
+                build_quad([inner, outer, next_outer, next_inner], [None; 4]).into(),
+            ));
+        }
+    }
+
+    // The ray with one more layer is closed by a triangle
+    let (inner, next_inner) = (point(first, common), point(second, common));
+    if inner != next_inner && counts[0] > common {
+        cells.push((
+            common,
+            build_triangle([inner, point(first, common + 1), next_inner], [None; 3]).into(),
+        ));
+    } else if inner != next_inner && counts[1] > common {
+        cells.push((
+            common,
+            build_triangle([inner, point(second, common + 1), next_inner], [None; 3]).into(),
+        ));
+    }
+
+    cells
+}
+
+fn ray_point(ray: &Ray, distance: f64) -> Point {
+    build_point(
+        ray.origin.x + ray.direction.x * distance,
+        ray.origin.y + ray.direction.y * distance,
+    )
+}
+
+/// Meshes the domain bounded by a PSLG with boundary layers along the segments tagged with one of the `walls`
+/// (see `extrude_boundary_layer`), and a Delaunay triangulation of `points` elsewhere (see `triangulate_pslg`).
+///
+/// The boundary edges of the mesh get the tag of the segment they are on.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// // A channel with a wall at the bottom, given from right to left to have the fluid above it
+/// let pslg = PslgBuilder::new(0.1)
+///     .line(build_point(2.0, 0.0), build_point(0.0, 0.0), "wall")
+///     .size(0.5)
+///     .line(build_point(0.0, 0.0), build_point(0.0, 1.0), "inlet")
+///     .line(build_point(0.0, 1.0), build_point(2.0, 1.0), "top")
+///     .line(build_point(2.0, 1.0), build_point(2.0, 0.0), "outlet")
+///     .build();
+/// let points: Vec<Point> = (1..20)
+///     .flat_map(|i| (1..10).map(move |j| build_point(i as f64 / 10.0 + 0.05, j as f64 / 10.0)))
+///     .collect();
+/// let options = BoundaryLayerOptions { first_height: 0.005, layers: 5, ..Default::default() };
+///
+/// let layered = mesh_with_boundary_layer(&pslg, &["wall"], &points, &options).unwrap();
+///
+/// let area: f64 = layered.mesh.cells.iter().map(|cell| cell.signed_area()).sum();
+/// assert!((area - 2.0).abs() < 1e-9);
+/// // The wall has 5 layers of 20 quads
+/// assert_eq!(layered.walls.iter().filter(|wall| wall.is_some()).count(), 100);
+/// ```
+pub fn mesh_with_boundary_layer(
+    pslg: &Pslg,
+    walls: &[&str],
+    points: &[Point],
+    options: &BoundaryLayerOptions,
+) -> Result<BoundaryLayerMesh, MeshError> {
+    let chains = wall_chains(pslg, walls)?;
+    let (layer, fronts) = extrude_chains(pslg, &chains, options);
+
+    let mut tags: HashMap<_, String> = pslg
+        .segments
+        .iter()
+        .map(|segment| {
+            (
+                edge_key(&pslg.points[segment.start], &pslg.points[segment.end]),
+                segment.tag.clone(),
+            )
+        })
+        .collect();
+
+    // Sides of the layers along another segment, which then starts at the front
+    let mut shortened: HashMap<(usize, usize), Point> = HashMap::new();
+    for (chain, front) in chains.iter().zip(&fronts) {
+        let last = front.points.len() - 1;
+        let sides = [
+            (
+                chain.points[0],
+                &front.points[..=front.sides[0]],
+                front.points[front.sides[0]],
+            ),
+            (
+                chain.points[chain.points.len() - 1],
+                &front.points[last - front.sides[1]..],
+                front.points[last - front.sides[1]],
+            ),
+        ];
+        for (end, (vertex, side, outer)) in chain.ends.iter().zip(sides) {
+            if let ChainEnd::Following(id) = end {
+                shortened.insert((*id, vertex), outer);
+                for edge in side.windows(2) {
+                    tags.insert(edge_key(&edge[0], &edge[1]), pslg.segments[*id].tag.clone());
+                }
+            }
+        }
+    }
+
+    let mut domain = Pslg::default();
+    let mut ids: HashMap<(u64, u64), usize> = HashMap::new();
+    let mut add_segment = |start: &Point, end: &Point, tag: &str| {
+        if start == end {
+            return;
+        }
+        let [start, end] = [start, end].map(|point| {
+            *ids.entry(point_key(point)).or_insert_with(|| {
+                domain.points.push(*point);
+                domain.points.len() - 1
+            })
+        });
+        domain.segments.push(Segment {
+            start,
+            end,
+            tag: tag.to_string(),
+        });
+    };
+
+    for (id, segment) in pslg.segments.iter().enumerate() {
+        if !walls.contains(&segment.tag.as_str()) {
+            let end_point = |vertex: usize| {
+                shortened
+                    .get(&(id, vertex))
+                    .copied()
+                    .unwrap_or(pslg.points[vertex])
+            };
+            let (start, end) = (end_point(segment.start), end_point(segment.end));
+            add_segment(&start, &end, &segment.tag);
+            tags.insert(edge_key(&start, &end), segment.tag.clone());
+        }
+    }
+    for (chain, front) in chains.iter().zip(&fronts) {
+        let following = |end: usize| matches!(chain.ends[end], ChainEnd::Following(_));
+        let first = if following(0) { front.sides[0] } else { 0 };
+        let last = front.points.len() - 1 - if following(1) { front.sides[1] } else { 0 };
+        for edge in front.points[first..=last].windows(2) {
+            add_segment(&edge[0], &edge[1], "front");
+        }
+
+        // A wall with a free end has fluid on both sides
+        if chain.ends.contains(&ChainEnd::Free) {
+            for (i, tag) in chain.tags.iter().enumerate() {
+                add_segment(
+                    &pslg.points[chain.points[i]],
+                    &pslg.points[chain.points[i + 1]],
+                    tag,
+                );
+            }
+        }
+    }
+
+    // Points too close to the front would prevent its edges from appearing in the triangulation
+    let front_edges: Vec<(Point, Point)> = fronts
+        .iter()
+        .flat_map(|front| front.points.windows(2).map(|edge| (edge[0], edge[1])))
+        .collect();
+    let far_from_front = |point: &Point| {
+        front_edges.iter().all(|(start, end)| {
+            let edge = start.segment_to(end);
+            let to_point = start.segment_to(point);
+            let along = ((&edge * &to_point) / (&edge * &edge)).clamp(0.0, 1.0);
+            (&to_point - &(&edge * along)).norm() > 0.5 * edge.norm()
+        })
+    };
+    let tree = build_cell_tree(&layer.cells);
+    let vertices: Vec<Point> = points
+        .iter()
+        .filter(|point| tree.locate(point).is_err() && far_from_front(point))
+        .copied()
+        .collect();
+
+    let triangulation = triangulate_pslg(&domain, &vertices)?;
+
+    let mut cell_walls: Vec<Option<String>> = layer.walls.into_iter().map(Some).collect();
+    cell_walls.resize(cell_walls.len() + triangulation.triangles.len(), None);
+    let mut cells = layer.cells;
+    cells.extend(triangulation.triangles.into_iter().map(MeshCell::from));
+    let mesh = MixedMesh::from_cells(cells);
+
+    let boundaries = mesh
+        .faces()
+        .into_iter()
+        .filter(|face| face.neighbor == Neighbor::Boundary)
+        .filter_map(|face| {
+            let vertices: Vec<&Point> = mesh.cells[face.owner].iter_vertices().collect();
+            let key = edge_key(
+                vertices[face.edge],
+                vertices[(face.edge + 1) % vertices.len()],
+            );
+            tags.get(&key).map(|tag| BoundaryFace {
+                cell: face.owner,
+                edge: face.edge,
+                tag: tag.clone(),
+            })
+        })
+        .collect();
+
+    Ok(BoundaryLayerMesh {
+        mesh,
+        walls: cell_walls,
+        boundaries,
+    })
+}
@@ -0,0 +1,314 @@
+//! Bounding volume hierarchy over the cells of a mesh, to find the cells around many points quickly.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use crate::error::*;
+use crate::triangulation::boxes_overlap;
+use crate::types::*;
+
+/// Maximum number of cells in a leaf of the tree.
+const LEAF_SIZE: usize = 4;
+
+/// Node of the tree, with the bounding box of all the cells under it.
+#[derive(Debug, Clone)]
+struct Node {
+    min: Point,
+    max: Point,
+    content: NodeContent,
+}
+
+#[derive(Debug, Clone)]
+enum NodeContent {
+    /// Indices of the two children in the nodes.
+    Children(usize, usize),
+    /// Range of the leaf in the sorted cell indices.
+    Leaf(usize, usize),
+}
+
+/// Bounding volume hierarchy built once over a mesh, answering point location, nearest cells and box queries
+/// in logarithmic time.
+#[derive(Debug, Clone)]
+pub struct CellTree<'a, T: Cell> {
+    cells: &'a [T],
+    nodes: Vec<Node>,
+    /// Cell indices, sorted so that the cells of each leaf are contiguous.
+    order: Vec<usize>,
+}
+
+/// Builds the tree over the cells, splitting each node at the median of the centers of its cells,
+/// along the longest side of their bounding box.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let vertices: Vec<Point> = (0..500)
+///     .map(|i| build_point((i as f64 * 0.7).sin(), (i as f64 * 1.3).cos()))
+///     .collect();
+/// let triangles = triangulate(&vertices).unwrap();
+/// let tree = build_cell_tree(&triangles);
+///
+/// for i in 0..50 {
+///     let point = build_point(0.6 * (i as f64).cos(), 0.6 * (i as f64 * 0.3).sin());
+///     let cell = tree.locate(&point).unwrap();
+///     assert!(triangles[cell].include(&point));
+/// }
+/// assert!(tree.locate(&build_point(2.0, 0.0)).is_err());
+///
+/// // The nearest cells of a point in the mesh start with the cell containing it
+/// let point = build_point(0.1, 0.2);
+/// let nearest = tree.nearest_cells(&point, 5);
+/// assert_eq!(nearest.len(), 5);
+/// assert!(triangles[nearest[0]].include(&point));
+///
+/// // The cells around the points of a box are in it
+/// let in_box = tree.cells_in_box(&build_point(-0.2, -0.2), &build_point(0.3, 0.1));
+/// for i in 0..=10 {
+///     let point = build_point(-0.2 + 0.05 * i as f64, -0.2 + 0.03 * i as f64);
+///     assert!(in_box.contains(&tree.locate(&point).unwrap()));
+/// }
+/// assert!(in_box.len() < triangles.len());
+/// assert_eq!(tree.cells_in_box(&build_point(-2.0, -2.0), &build_point(2.0, 2.0)).len(), triangles.len());
+/// assert!(tree.cells_in_box(&build_point(2.0, 2.0), &build_point(3.0, 3.0)).is_empty());
+/// ```
+pub fn build_cell_tree<T: Cell>(cells: &[T]) -> CellTree<'_, T> {
+    let mut tree = CellTree {
+        cells,
+        nodes: Vec::new(),
+        order: (0..cells.len()).collect(),
+    };
+    let boxes: Vec<(Point, Point)> = cells.iter().map(cell_box).collect();
+    let centers: Vec<Point> = cells.iter().map(|cell| cell.center()).collect();
+
+    if !cells.is_empty() {
+        tree.build_node(&boxes, &centers, 0, cells.len());
+    }
+
+    tree
+}
+
+impl<'a, T: Cell> CellTree<'a, T> {
+    /// Adds the node of the cells between `start` and `end` in the order, and returns its index.
+    fn build_node(
+        &mut self,
+        boxes: &[(Point, Point)],
+        centers: &[Point],
+        start: usize,
+        end: usize,
+    ) -> usize {
+        let (mut min, mut max) = boxes[self.order[start]];
+        for &id in &self.order[start..end] {
+            min = build_point(min.x.min(boxes[id].0.x), min.y.min(boxes[id].0.y));
+            max = build_point(max.x.max(boxes[id].1.x), max.y.max(boxes[id].1.y));
+        }
+
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            min,
+            max,
+            content: NodeContent::Leaf(start, end),
+        });
+        if end - start <= LEAF_SIZE {
+            return id;
+        }
+
+        let horizontal = max.x - min.x >= max.y - min.y;
+        let coordinate = |cell: &usize| {
+            if horizontal {
+                centers[*cell].x
+            } else {
+                centers[*cell].y
+            }
+        };
+        let middle = (start + end) / 2;
+        self.order[start..end].select_nth_unstable_by(middle - start, |a, b| {
+            coordinate(a).total_cmp(&coordinate(b))
+        });
+
+        let first = self.build_node(boxes, centers, start, middle);
+        let second = self.build_node(boxes, centers, middle, end);
+        self.nodes[id].content = NodeContent::Children(first, second);
+
+        id
+    }
+
+    /// Finds a cell containing the point, going only through the nodes whose box contains it.
+    pub fn locate(&self, point: &Point) -> Result<usize, MeshError> {
+        let mut stack = if self.nodes.is_empty() {
+            vec![]
+        } else {
+            vec![0]
+        };
+
+        while let Some(id) = stack.pop() {
+            let node = &self.nodes[id];
+            if !boxes_overlap((&node.min, &node.max), (point, point)) {
+                continue;
+            }
+            match node.content {
+                NodeContent::Children(first, second) => stack.extend([second, first]),
+                NodeContent::Leaf(start, end) => {
+                    if let Some(&cell) = self.order[start..end]
+                        .iter()
+                        .find(|cell| self.cells[**cell].include(point))
+                    {
+                        return Ok(cell);
+                    }
+                }
+            }
+        }
+
+        Err(MeshError::PointOutsideDomain { point: *point })
+    }
+
+    /// Gives the `count` cells closest to the point, the closest first.
+    /// The distance to a cell is zero inside of it, and the distance to its closest edge otherwise.
+    pub fn nearest_cells(&self, point: &Point, count: usize) -> Vec<usize> {
+        let mut nearest = Vec::with_capacity(count);
+        if self.nodes.is_empty() || count == 0 {
+            return nearest;
+        }
+
+        // Nodes and cells are visited by increasing distance, a node being closer than any cell in it
+        let mut queue = BinaryHeap::from([Candidate {
+            distance: box_distance(point, &self.nodes[0].min, &self.nodes[0].max),
+            item: Item::Node(0),
+        }]);
+
+        while let Some(Candidate { item, .. }) = queue.pop() {
+            match item {
+                Item::Cell(cell) => {
+                    nearest.push(cell);
+                    if nearest.len() == count {
+                        break;
+                    }
+                }
+                Item::Node(id) => match self.nodes[id].content {
+                    NodeContent::Children(first, second) => {
+                        for child in [first, second] {
+                            let node = &self.nodes[child];
+                            queue.push(Candidate {
+                                distance: box_distance(point, &node.min, &node.max),
+                                item: Item::Node(child),
+                            });
+                        }
+                    }
+                    NodeContent::Leaf(start, end) => {
+                        for &cell in &self.order[start..end] {
+                            queue.push(Candidate {
+                                distance: cell_distance(&self.cells[cell], point),
+                                item: Item::Cell(cell),
+                            });
+                        }
+                    }
+                },
+            }
+        }
+
+        nearest
+    }
+
+    /// Gives the cells whose bounding box intersects the box from `min` to `max`.
+    pub fn cells_in_box(&self, min: &Point, max: &Point) -> Vec<usize> {
+        let mut cells = Vec::new();
+        let mut stack = if self.nodes.is_empty() {
+            vec![]
+        } else {
+            vec![0]
+        };
+
+        while let Some(id) = stack.pop() {
+            let node = &self.nodes[id];
+            if !boxes_overlap((&node.min, &node.max), (min, max)) {
+                continue;
+            }
+            match node.content {
+                NodeContent::Children(first, second) => stack.extend([second, first]),
+                NodeContent::Leaf(start, end) => cells.extend(
+                    self.order[start..end]
+                        .iter()
+                        .filter(|cell| {
+                            let (cell_min, cell_max) = cell_box(&self.cells[**cell]);
+                            boxes_overlap((&cell_min, &cell_max), (min, max))
+                        })
+                        .copied(),
+                ),
+            }
+        }
+
+        cells
+    }
+}
+
+/// Node or cell waiting in the queue of `nearest_cells`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Item {
+    Node(usize),
+    Cell(usize),
+}
+
+/// Entry of the queue of `nearest_cells`, the closest being the greatest.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate {
+    distance: f64,
+    item: Item,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // At the same distance, the cells come before the nodes so that they are given as soon as possible
+        other
+            .distance
+            .total_cmp(&self.distance)
+            .then_with(|| match (self.item, other.item) {
+                (Item::Cell(_), Item::Node(_)) => Ordering::Greater,
+                (Item::Node(_), Item::Cell(_)) => Ordering::Less,
+                _ => Ordering::Equal,
+            })
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn cell_box<T: Cell>(cell: &T) -> (Point, Point) {
+    let mut vertices = cell.iter_vertices();
+    let first = vertices.next().copied().unwrap_or(build_point(0.0, 0.0));
+
+    vertices.fold((first, first), |(min, max), vertex| {
+        (
+            build_point(min.x.min(vertex.x), min.y.min(vertex.y)),
+            build_point(max.x.max(vertex.x), max.y.max(vertex.y)),
+        )
+    })
+}
+
+fn box_distance(point: &Point, min: &Point, max: &Point) -> f64 {
+    let dx = (min.x - point.x).max(point.x - max.x).max(0.0);
+    let dy = (min.y - point.y).max(point.y - max.y).max(0.0);
+    (dx * dx + dy * dy).sqrt()
+}
+
+/// Distance from a point to a cell, zero if the point is inside.
+fn cell_distance<T: Cell>(cell: &T, point: &Point) -> f64 {
+    if cell.include(point) {
+        return 0.0;
+    }
+
+    let vertices: Vec<&Point> = cell.iter_vertices().collect();
+    (0..vertices.len())
+        .map(|i| {
+            let (start, end) = (vertices[i], vertices[(i + 1) % vertices.len()]);
+            let (edge, to_point) = (start.segment_to(end), start.segment_to(point));
+            let ratio = ((&edge * &to_point) / (&edge * &edge)).clamp(0.0, 1.0);
+            build_vector(to_point.x - ratio * edge.x, to_point.y - ratio * edge.y).norm()
+        })
+        .fold(f64::MAX, f64::min)
+}
@@ -0,0 +1,595 @@
+//! Divide and conquer Delaunay triangulation (Guibas and Stolfi), for large static point clouds.
+//!
+//! The points are sorted, split in halves until there are two or three of them, and the sub-triangulations are merged back.
+//! The mesh is held in a quad-edge structure while it is built, then converted to triangles.
+
+use std::collections::HashSet;
+
+use crate::error::*;
+use crate::triangulation::flip_edge;
+use crate::types::*;
+use crate::validation::point_key;
+
+/// Quad-edge structure: each edge is stored as four consecutive directed edges,
+/// the edge, its dual rotated counter-clockwise, the edge reversed and the dual rotated clockwise.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct QuadEdges {
+    /// Next edge counter-clockwise around the origin of each directed edge.
+    next: Vec<usize>,
+    /// Index of the origin point of each directed edge, unused for the dual edges.
+    origin: Vec<usize>,
+    deleted: Vec<bool>,
+}
+
+fn rot(edge: usize) -> usize {
+    (edge & !3) | ((edge + 1) & 3)
+}
+
+fn sym(edge: usize) -> usize {
+    (edge & !3) | ((edge + 2) & 3)
+}
+
+fn rot_inv(edge: usize) -> usize {
+    (edge & !3) | ((edge + 3) & 3)
+}
+
+impl QuadEdges {
+    fn onext(&self, edge: usize) -> usize {
+        self.next[edge]
+    }
+
+    fn oprev(&self, edge: usize) -> usize {
+        rot(self.onext(rot(edge)))
+    }
+
+    fn lnext(&self, edge: usize) -> usize {
+        rot(self.onext(rot_inv(edge)))
+    }
+
+    fn rprev(&self, edge: usize) -> usize {
+        self.onext(sym(edge))
+    }
+
+    fn org(&self, edge: usize) -> usize {
+        self.origin[edge]
+    }
+
+    fn dest(&self, edge: usize) -> usize {
+        self.origin[sym(edge)]
+    }
+
+    fn make_edge(&mut self, start: usize, end: usize) -> usize {
+        let edge = self.next.len();
+        self.next
+            .extend_from_slice(&[edge, edge + 3, edge + 2, edge + 1]);
+        self.origin.extend_from_slice(&[start, 0, end, 0]);
+        self.deleted.extend_from_slice(&[false; 4]);
+        edge
+    }
+
+    fn splice(&mut self, a: usize, b: usize) {
+        let (alpha, beta) = (rot(self.onext(a)), rot(self.onext(b)));
+        self.next.swap(a, b);
+        self.next.swap(alpha, beta);
+    }
+
+    /// Adds an edge from the end of `a` to the start of `b`, with the same left face as both.
+    fn connect(&mut self, a: usize, b: usize) -> usize {
+        let edge = self.make_edge(self.dest(a), self.org(b));
+        self.splice(edge, self.lnext(a));
+        self.splice(sym(edge), b);
+        edge
+    }
+
+    fn delete_edge(&mut self, edge: usize) {
+        self.splice(edge, self.oprev(edge));
+        self.splice(sym(edge), self.oprev(sym(edge)));
+        for i in 0..4 {
+            self.deleted[(edge & !3) + i] = true;
+        }
+    }
+
+    /// Moves the edges of another structure after the ones of this structure, and returns the shift of their indices.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn append(&mut self, other: QuadEdges) -> usize {
+        let offset = self.next.len();
+        self.next
+            .extend(other.next.into_iter().map(|next| next + offset));
+        self.origin.extend(other.origin);
+        self.deleted.extend(other.deleted);
+        offset
+    }
+}
+
+/// A triangle is flat if its height is smaller than this ratio times the magnitude of its coordinates:
+/// its vertices are only known up to rounding errors of this order.
+const FLAT_TOLERANCE: f64 = 8.0 * f64::EPSILON;
+
+// The predicates below give exact signs, as the merge of the triangulations relies on its tests never contradicting
+// each other. They are computed in floating point when the rounding error cannot change the sign (with the error
+// bounds of Shewchuk, "Adaptive precision floating-point arithmetic and fast robust geometric predicates"),
+// and as a sum of products of coordinates without any rounding error otherwise.
+
+/// Sign of the orientation of the triangle (a, b, c): positive if it is counter-clockwise, zero if the points are collinear.
+fn orientation(a: &Point, b: &Point, c: &Point) -> f64 {
+    let (left, right) = ((a.x - c.x) * (b.y - c.y), (a.y - c.y) * (b.x - c.x));
+    let determinant = left - right;
+    let error_bound = (3.0 + 8.0 * f64::EPSILON) * f64::EPSILON / 2.0 * (left.abs() + right.abs());
+    // A zero bound means that every product is zero, without rounding error
+    if determinant.abs() > error_bound || error_bound == 0.0 {
+        return determinant;
+    }
+
+    let mut expansion = Vec::new();
+    for (p, q) in [(a, b), (b, c), (c, a)] {
+        add_product(&mut expansion, &[p.x, q.y]);
+        add_product(&mut expansion, &[-p.y, q.x]);
+    }
+    expansion_sign(&expansion)
+}
+
+/// Sign telling if `d` is in the circumcircle of the counter-clockwise triangle (a, b, c): positive if it is inside,
+/// zero if it is on the circle.
+fn in_circle(a: &Point, b: &Point, c: &Point, d: &Point) -> f64 {
+    let (ad, bd, cd) = (d.segment_to(a), d.segment_to(b), d.segment_to(c));
+    let lifts = [&ad * &ad, &bd * &bd, &cd * &cd];
+    let crosses = [
+        (bd.x * cd.y, cd.x * bd.y),
+        (cd.x * ad.y, ad.x * cd.y),
+        (ad.x * bd.y, bd.x * ad.y),
+    ];
+    let determinant: f64 = (0..3)
+        .map(|i| lifts[i] * (crosses[i].0 - crosses[i].1))
+        .sum();
+    let permanent: f64 = (0..3)
+        .map(|i| lifts[i] * (crosses[i].0.abs() + crosses[i].1.abs()))
+        .sum();
+    let error_bound = (10.0 + 48.0 * f64::EPSILON) * f64::EPSILON / 2.0 * permanent;
+    // A zero bound means that every product is zero, without rounding error
+    if determinant.abs() > error_bound || error_bound == 0.0 {
+        return determinant;
+    }
+
+    // Laplace expansion of the determinant of the rows (x, y, x² + y², 1) along its last column
+    let rows = [a, b, c, d];
+    let mut expansion = Vec::new();
+    for (omitted, sign) in [(0, -1.0), (1, 1.0), (2, -1.0), (3, 1.0)] {
+        let [p, q, r] = [0, 1, 2].map(|i| rows[if i < omitted { i } else { i + 1 }]);
+        for (lifted, u, v) in [(p, q, r), (q, r, p), (r, p, q)] {
+            for coordinate in [lifted.x, lifted.y] {
+                add_product(&mut expansion, &[sign * coordinate, coordinate, u.x, v.y]);
+                add_product(&mut expansion, &[-sign * coordinate, coordinate, u.y, v.x]);
+            }
+        }
+    }
+    expansion_sign(&expansion)
+}
+
+/// Adds the exact product of some numbers to an expansion, i.e. a sum of floating point numbers without rounding error,
+/// sorted by increasing magnitude.
+fn add_product(expansion: &mut Vec<f64>, factors: &[f64]) {
+    let mut product = vec![factors[0]];
+    for factor in &factors[1..] {
+        let mut scaled = Vec::with_capacity(2 * product.len());
+        for component in product {
+            let rounded = component * factor;
+            grow_expansion(&mut scaled, component.mul_add(*factor, -rounded));
+            grow_expansion(&mut scaled, rounded);
+        }
+        product = scaled;
+    }
+
+    for component in product {
+        grow_expansion(expansion, component);
+    }
+}
+
+/// Adds a number to an expansion, dropping the components which become zero.
+fn grow_expansion(expansion: &mut Vec<f64>, value: f64) {
+    let mut sum = value;
+    let mut length = 0;
+    for i in 0..expansion.len() {
+        let component = expansion[i];
+        let total = sum + component;
+        let rounded = total - sum;
+        let error = (sum - (total - rounded)) + (component - rounded);
+        sum = total;
+        if error != 0.0 {
+            expansion[length] = error;
+            length += 1;
+        }
+    }
+    expansion.truncate(length);
+    if sum != 0.0 {
+        expansion.push(sum);
+    }
+}
+
+/// The largest component of an expansion gives its sign.
+fn expansion_sign(expansion: &[f64]) -> f64 {
+    expansion.last().copied().unwrap_or(0.0)
+}
+
+/// Triangulates a point cloud with the divide and conquer algorithm of Guibas and Stolfi.
+///
+/// Unlike `triangulate`, no super triangle is used, so the triangles cover the convex hull of the points,
+/// except for the triangles along it which are flat up to rounding errors: these are removed, so that the boundary
+/// goes through every point of a nearly straight side of the hull, even those slightly outside of it.
+/// The geometric tests are exact, so that grids and cocircular points are handled,
+/// and duplicated points are only used once.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let vertices: Vec<Point> = (0..200)
+///     .map(|i| build_point((i as f64 * 0.7).sin(), (i as f64 * 1.3).cos()))
+///     .collect();
+///
+/// let triangles = triangulate_divide_and_conquer(&vertices).unwrap();
+///
+/// assert!(validate(&vertices, &triangles, &[]).is_empty());
+/// // Euler's formula, with 13 points on the convex hull
+/// assert_eq!(triangles.len(), 2 * vertices.len() - 13 - 2);
+/// assert!(matches!(
+///     triangulate_divide_and_conquer(&vertices[..2]),
+///     Err(MeshError::DegenerateInput)
+/// ));
+///
+/// // The points of the bottom side are alternately on the line y = 0 and 1e-15 below it
+/// let mut vertices: Vec<Point> = (0..=10)
+///     .map(|i| build_point(i as f64 * 0.3, if i % 2 == 1 { -1e-15 } else { 0.0 }))
+///     .collect();
+/// vertices.extend((1..5).flat_map(|j| (0..=5).map(move |i| build_point(i as f64 * 0.6, j as f64 * 0.5))));
+///
+/// let triangles = triangulate_divide_and_conquer(&vertices).unwrap();
+///
+/// // The flat triangles joining the points below the line are removed, so the 11 points are on the boundary
+/// assert_eq!(triangles.len(), 2 * vertices.len() - (11 + 6 + 2 * 3) - 2);
+/// assert!(triangles.iter().all(|triangle| triangle.signed_area() > 1e-3));
+/// assert!(validate(&vertices, &triangles, &[]).is_empty());
+/// ```
+pub fn triangulate_divide_and_conquer(vertices: &[Point]) -> Result<Vec<Triangle>, MeshError> {
+    let points = sorted_unique_points(vertices)?;
+
+    let mut edges = QuadEdges::default();
+    delaunay(&mut edges, &points, 0, points.len());
+
+    quad_edges_to_triangles(&edges, &points)
+}
+
+/// Sorts the points by x then y, without duplicates.
+pub(crate) fn sorted_unique_points(vertices: &[Point]) -> Result<Vec<Point>, MeshError> {
+    if vertices.is_empty() {
+        return Err(MeshError::EmptyInput);
+    }
+
+    let mut seen = HashSet::new();
+    let mut points: Vec<Point> = vertices
+        .iter()
+        .filter(|point| seen.insert(point_key(point)))
+        .copied()
+        .collect();
+    points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+
+    if points.len() < 3 {
+        return Err(MeshError::DegenerateInput);
+    }
+    Ok(points)
+}
+
+/// Triangulates the points from `start` to `end` (excluded), and returns the counter-clockwise convex hull edge
+/// out of the leftmost point and the clockwise convex hull edge out of the rightmost point.
+pub(crate) fn delaunay(
+    edges: &mut QuadEdges,
+    points: &[Point],
+    start: usize,
+    end: usize,
+) -> (usize, usize) {
+    match end - start {
+        2 => {
+            let a = edges.make_edge(start, start + 1);
+            (a, sym(a))
+        }
+        3 => {
+            let (p1, p2, p3) = (&points[start], &points[start + 1], &points[start + 2]);
+            let a = edges.make_edge(start, start + 1);
+            let b = edges.make_edge(start + 1, start + 2);
+            edges.splice(sym(a), b);
+
+            if orientation(p1, p2, p3) > 0.0 {
+                edges.connect(b, a);
+                (a, sym(b))
+            } else if orientation(p1, p3, p2) > 0.0 {
+                let c = edges.connect(b, a);
+                (sym(c), c)
+            } else {
+                (a, sym(b))
+            }
+        }
+        count => {
+            let middle = start + count / 2;
+            let left = delaunay(edges, points, start, middle);
+            let right = delaunay(edges, points, middle, end);
+            merge(edges, points, left, right)
+        }
+    }
+}
+
+/// Merges two triangulations separated by a vertical line, given by their hull edges as returned by `delaunay`.
+pub(crate) fn merge(
+    edges: &mut QuadEdges,
+    points: &[Point],
+    (mut left_outer, mut left_inner): (usize, usize),
+    (mut right_inner, mut right_outer): (usize, usize),
+) -> (usize, usize) {
+    let point = |edges: &QuadEdges, edge: usize, at_end: bool| {
+        &points[if at_end {
+            edges.dest(edge)
+        } else {
+            edges.org(edge)
+        }]
+    };
+    let left_of = |edges: &QuadEdges, x: &Point, edge: usize| {
+        orientation(x, point(edges, edge, false), point(edges, edge, true)) > 0.0
+    };
+    let right_of = |edges: &QuadEdges, x: &Point, edge: usize| {
+        orientation(x, point(edges, edge, true), point(edges, edge, false)) > 0.0
+    };
+    // Lower common tangent of the two hulls
+    loop {
+        if left_of(edges, point(edges, right_inner, false), left_inner) {
+            left_inner = edges.lnext(left_inner);
+        } else if right_of(edges, point(edges, left_inner, false), right_inner) {
+            right_inner = edges.rprev(right_inner);
+        } else {
+            break;
+        }
+    }
+
+    let mut base = edges.connect(sym(right_inner), left_inner);
+    if edges.org(left_inner) == edges.org(left_outer) {
+        left_outer = sym(base);
+    }
+    if edges.org(right_inner) == edges.org(right_outer) {
+        right_outer = base;
+    }
+
+    // The edges are added from the bottom to the top, removing the edges which are not Delaunay anymore
+    loop {
+        let valid = |edges: &QuadEdges, edge: usize, base: usize| {
+            right_of(edges, point(edges, edge, true), base)
+        };
+
+        let mut left_candidate = edges.onext(sym(base));
+        if valid(edges, left_candidate, base) {
+            while in_circle(
+                point(edges, base, true),
+                point(edges, base, false),
+                point(edges, left_candidate, true),
+                point(edges, edges.onext(left_candidate), true),
+            ) > 0.0
+            {
+                let next = edges.onext(left_candidate);
+                edges.delete_edge(left_candidate);
+                left_candidate = next;
+            }
+        }
+
+        let mut right_candidate = edges.oprev(base);
+        if valid(edges, right_candidate, base) {
+            while in_circle(
+                point(edges, base, true),
+                point(edges, base, false),
+                point(edges, right_candidate, true),
+                point(edges, edges.oprev(right_candidate), true),
+            ) > 0.0
+            {
+                let next = edges.oprev(right_candidate);
+                edges.delete_edge(right_candidate);
+                right_candidate = next;
+            }
+        }
+
+        let (left_valid, right_valid) = (
+            valid(edges, left_candidate, base),
+            valid(edges, right_candidate, base),
+        );
+        if !left_valid && !right_valid {
+            break;
+        }
+
+        base = if !left_valid
+            || (right_valid
+                && in_circle(
+                    point(edges, left_candidate, true),
+                    point(edges, left_candidate, false),
+                    point(edges, right_candidate, false),
+                    point(edges, right_candidate, true),
+                ) > 0.0)
+        {
+            edges.connect(right_candidate, sym(base))
+        } else {
+            edges.connect(sym(base), sym(left_candidate))
+        };
+    }
+
+    (left_outer, right_outer)
+}
+
+/// Converts a triangulation built by `delaunay` to triangles, without the flat ones.
+pub(crate) fn quad_edges_to_triangles(
+    edges: &QuadEdges,
+    points: &[Point],
+) -> Result<Vec<Triangle>, MeshError> {
+    let mut triangles = edges_to_triangles(edges, points);
+    if triangles.is_empty() {
+        return Err(MeshError::DegenerateInput);
+    }
+    remove_flat_triangles(&mut triangles)?;
+    Ok(triangles)
+}
+
+/// Converts the faces of the quad-edge structure to triangles with their adjacencies.
+/// The outer face is the only one which is not a counter-clockwise triangle.
+fn edges_to_triangles(edges: &QuadEdges, points: &[Point]) -> Vec<Triangle> {
+    const NONE: usize = usize::MAX;
+    // Triangle on the left of each directed edge
+    let mut face = vec![NONE; edges.next.len()];
+    let mut faces: Vec<[usize; 3]> = Vec::new();
+
+    // Primal edges are the even ones
+    for start in (0..edges.next.len()).step_by(2) {
+        if edges.deleted[start] || face[start] != NONE {
+            continue;
+        }
+        let second = edges.lnext(start);
+        let third = edges.lnext(second);
+        if edges.lnext(third) != start
+            || orientation(
+                &points[edges.org(start)],
+                &points[edges.org(second)],
+                &points[edges.org(third)],
+            ) <= 0.0
+        {
+            continue;
+        }
+
+        for edge in [start, second, third] {
+            face[edge] = faces.len();
+        }
+        faces.push([start, second, third]);
+    }
+
+    faces
+        .iter()
+        .map(|face_edges| {
+            let neighbor = |edge: usize| match face[sym(edge)] {
+                NONE => Some(Neighbor::Boundary),
+                id => Some(Neighbor::Cell(id)),
+            };
+            build_triangle(
+                face_edges.map(|edge| points[edges.org(edge)]),
+                face_edges.map(neighbor),
+            )
+        })
+        .collect()
+}
+
+/// Points collinear up to rounding errors can still form flat triangles, mostly along the convex hull.
+/// If the longest edge of one of these triangles is on the boundary, the triangle is removed.
+/// Otherwise this edge is flipped, so that the middle vertex is linked to the opposite vertex.
+///
+/// An edge is only flipped if the two new triangles are not flat, so that fans of flat triangles
+/// are removed one triangle at a time from their base.
+fn remove_flat_triangles(triangles: &mut Vec<Triangle>) -> Result<(), MeshError> {
+    let longest_edge = |vertices: &[Point; 3]| {
+        (0..3)
+            .map(|edge| {
+                (
+                    edge,
+                    vertices[edge].segment_to(&vertices[(edge + 1) % 3]).norm(),
+                )
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .unwrap()
+            .0
+    };
+    // The vertices are sorted, so that the rounding errors do not depend on their order
+    let is_flat = |mut vertices: [Point; 3]| {
+        vertices.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+        let (ab, ac) = (
+            vertices[0].segment_to(&vertices[1]),
+            vertices[0].segment_to(&vertices[2]),
+        );
+        let magnitude = vertices
+            .iter()
+            .map(|vertex| vertex.x.abs().max(vertex.y.abs()))
+            .fold(0.0, f64::max);
+        let longest = vertices[1]
+            .segment_to(&vertices[2])
+            .norm()
+            .max(ac.norm())
+            .max(ab.norm());
+        (ab.x * ac.y - ab.y * ac.x).abs() <= FLAT_TOLERANCE * longest * magnitude
+    };
+
+    let mut is_removed = vec![false; triangles.len()];
+    let mut stack: Vec<usize> = (0..triangles.len()).collect();
+    while let Some(id) = stack.pop() {
+        let vertices = triangles[id].vertices;
+        if is_removed[id] || !is_flat(vertices) {
+            continue;
+        }
+        let edge = longest_edge(&vertices);
+        let (start, end, middle) = (
+            vertices[edge],
+            vertices[(edge + 1) % 3],
+            vertices[(edge + 2) % 3],
+        );
+
+        let changed = match triangles[id].adjacencies[edge] {
+            Some(Neighbor::Cell(neighbor)) => {
+                let opposite = *triangles[neighbor]
+                    .vertices
+                    .iter()
+                    .find(|vertex| **vertex != start && **vertex != end)
+                    .unwrap();
+                if is_flat([middle, start, opposite]) || is_flat([middle, opposite, end]) {
+                    continue;
+                }
+                flip_edge(triangles, id, edge)?;
+                [id, neighbor]
+            }
+            _ => {
+                is_removed[id] = true;
+                for adjacency in triangles[id].adjacencies {
+                    if let Some(Neighbor::Cell(next)) = adjacency {
+                        for next_adjacency in triangles[next].adjacencies.iter_mut() {
+                            if *next_adjacency == Some(Neighbor::Cell(id)) {
+                                *next_adjacency = Some(Neighbor::Boundary);
+                            }
+                        }
+                    }
+                }
+                [id, id]
+            }
+        };
+
+        // The flat triangles next to the changed ones may be flipped or removed now
+        for changed in changed {
+            for adjacency in triangles[changed].adjacencies {
+                if let Some(Neighbor::Cell(next)) = adjacency {
+                    stack.push(next);
+                }
+            }
+        }
+    }
+
+    let mut new_ids: Vec<Option<usize>> = Vec::with_capacity(triangles.len());
+    let mut count = 0;
+    for is_removed in &is_removed {
+        if *is_removed {
+            new_ids.push(None);
+        } else {
+            new_ids.push(Some(count));
+            count += 1;
+        }
+    }
+
+    let mut id = 0;
+    triangles.retain(|_| {
+        id += 1;
+        new_ids[id - 1].is_some()
+    });
+    for triangle in triangles.iter_mut() {
+        for adjacency in triangle.adjacencies.iter_mut() {
+            if let Some(Neighbor::Cell(old_id)) = *adjacency {
+                *adjacency = Some(new_ids[old_id].map_or(Neighbor::Boundary, Neighbor::Cell));
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,80 @@
+//! Errors returned by the triangulation pipeline instead of panicking.
+
+use std::fmt;
+
+use crate::types::*;
+
+/// Every way the meshing of a point cloud can fail.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MeshError {
+    /// The point is not inside any cell of the mesh.
+    PointOutsideDomain { point: Point },
+    /// The point is already a vertex of the mesh.
+    DuplicatePoint { point: Point },
+    /// The point is not a vertex of the mesh.
+    VertexNotFound { point: Point },
+    /// No vertex has this index, or it was removed.
+    UnknownVertex { vertex: usize },
+    /// The points all coincide or are collinear, so no cell can be built.
+    DegenerateInput,
+    /// No point was given.
+    EmptyInput,
+    /// A line of a geometry file cannot be read.
+    ParseError { line: usize, reason: &'static str },
+    /// A file cannot be read, with the kind and the message of the I/O error.
+    Io {
+        kind: std::io::ErrorKind,
+        message: String,
+    },
+    /// The cell has a null or negative area.
+    InvertedCell { cell: usize },
+    /// The metric at the point is not symmetric positive definite.
+    InvalidMetric { point: Point },
+    /// A field does not have one value per cell or per vertex of the mesh.
+    FieldSizeMismatch { expected: usize, found: usize },
+    /// The adjacencies of the mesh are not consistent anymore.
+    TopologyCorruption { cell: usize, reason: &'static str },
+}
+
+impl fmt::Display for MeshError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MeshError::PointOutsideDomain { point } => {
+                write!(f, "point ({}, {}) is outside of the mesh", point.x, point.y)
+            }
+            MeshError::DuplicatePoint { point } => {
+                write!(f, "point ({}, {}) is already in the mesh", point.x, point.y)
+            }
+            MeshError::VertexNotFound { point } => {
+                write!(
+                    f,
+                    "point ({}, {}) is not a vertex of the mesh",
+                    point.x, point.y
+                )
+            }
+            MeshError::UnknownVertex { vertex } => write!(f, "vertex {} does not exist", vertex),
+            MeshError::DegenerateInput => write!(f, "points are all coincident or collinear"),
+            MeshError::EmptyInput => write!(f, "no point to mesh"),
+            MeshError::ParseError { line, reason } => {
+                write!(f, "cannot read line {}: {}", line, reason)
+            }
+            MeshError::Io { message, .. } => write!(f, "cannot read the file: {}", message),
+            MeshError::InvertedCell { cell } => write!(f, "cell {} is inverted", cell),
+            MeshError::InvalidMetric { point } => {
+                write!(
+                    f,
+                    "metric at ({}, {}) is not positive definite",
+                    point.x, point.y
+                )
+            }
+            MeshError::FieldSizeMismatch { expected, found } => {
+                write!(f, "field has {} values instead of {}", found, expected)
+            }
+            MeshError::TopologyCorruption { cell, reason } => {
+                write!(f, "mesh topology corrupted at cell {}: {}", cell, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MeshError {}
@@ -0,0 +1,720 @@
+//! Description of the geometry to mesh, as curves discretised into a planar straight-line graph (PSLG).
+//!
+//! The PSLG gives the boundary points and the segments joining them,
+//! each segment keeping the tag of the curve it comes from.
+
+use std::collections::{HashMap, VecDeque};
+use std::f64::consts::PI;
+
+use crate::error::*;
+use crate::structured::BoundaryFace;
+use crate::triangulation::*;
+use crate::types::*;
+use crate::validation::edge_key;
+
+/// Curve of the geometry, parametrised from 0 at its start to 1 at its end.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Curve {
+    Line(Point, Point),
+    /// Arc of circle, from the angle `start` to the angle `end` (in radians).
+    Arc {
+        center: Point,
+        radius: f64,
+        start: f64,
+        end: f64,
+    },
+    /// Polyline, parametrised by its arc length.
+    Polyline(Vec<Point>),
+    /// Clamped cubic B-spline, going through its first and last control points.
+    /// Fewer than four control points give a spline of lower degree.
+    BSpline(Vec<Point>),
+}
+
+impl Curve {
+    /// Creates a full circle, counter-clockwise from the angle 0.
+    pub fn circle(center: Point, radius: f64) -> Curve {
+        Curve::Arc {
+            center,
+            radius,
+            start: 0.0,
+            end: 2.0 * PI,
+        }
+    }
+
+    /// Gives the point of the curve at the parameter `t`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use meshing::*;
+    ///
+    /// let curve = Curve::Polyline(vec![
+    ///     build_point(0.0, 0.0),
+    ///     build_point(1.0, 0.0),
+    ///     build_point(1.0, 3.0),
+    /// ]);
+    ///
+    /// assert_eq!(curve.point_at(0.5), build_point(1.0, 1.0));
+    /// assert_eq!(curve.reversed().point_at(0.25), build_point(1.0, 2.0));
+    /// ```
+    pub fn point_at(&self, t: f64) -> Point {
+        match self {
+            Curve::Line(start, end) => &(start * (1.0 - t)) + &(end * t),
+            Curve::Arc {
+                center,
+                radius,
+                start,
+                end,
+            } => {
+                let angle = start + (end - start) * t;
+                build_point(
+                    center.x + radius * angle.cos(),
+                    center.y + radius * angle.sin(),
+                )
+            }
+            Curve::Polyline(points) => {
+                let lengths: Vec<f64> = points
+                    .windows(2)
+                    .map(|segment| segment[0].segment_to(&segment[1]).norm())
+                    .collect();
+                let mut remaining = t * lengths.iter().sum::<f64>();
+
+                for (i, length) in lengths.iter().enumerate() {
+                    if remaining <= *length && *length > 0.0 {
+                        let ratio = remaining / length;
+                        return &(&points[i] * (1.0 - ratio)) + &(&points[i + 1] * ratio);
+                    }
+                    remaining -= length;
+                }
+
+                points[points.len() - 1]
+            }
+            Curve::BSpline(control_points) => de_boor(control_points, t),
+        }
+    }
+
+    /// Gives the same curve, going from its end to its start.
+    pub fn reversed(&self) -> Curve {
+        match self {
+            Curve::Line(start, end) => Curve::Line(*end, *start),
+            Curve::Arc {
+                center,
+                radius,
+                start,
+                end,
+            } => Curve::Arc {
+                center: *center,
+                radius: *radius,
+                start: *end,
+                end: *start,
+            },
+            Curve::Polyline(points) => Curve::Polyline(points.iter().rev().copied().collect()),
+            Curve::BSpline(control_points) => {
+                Curve::BSpline(control_points.iter().rev().copied().collect())
+            }
+        }
+    }
+
+    /// Gives the length of the curve, approximated by a fine polyline for splines.
+    pub fn length(&self) -> f64 {
+        match self {
+            Curve::Line(start, end) => start.segment_to(end).norm(),
+            Curve::Arc {
+                radius, start, end, ..
+            } => radius * (end - start).abs(),
+            Curve::Polyline(points) => polyline_length(points),
+            Curve::BSpline(_) => self.arc_lengths().last().map_or(0.0, |(_, length)| *length),
+        }
+    }
+
+    /// Discretises the curve into points spaced by at most `size`, evenly along the curve.
+    /// Both ends are included, and the vertices of a polyline are kept.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use meshing::*;
+    ///
+    /// let spline = Curve::BSpline(vec![
+    ///     build_point(0.0, 0.0),
+    ///     build_point(1.0, 1.0),
+    ///     build_point(2.0, -1.0),
+    ///     build_point(3.0, 0.0),
+    /// ]);
+    /// let points = spline.discretize(0.1);
+    ///
+    /// assert_eq!(points[0], build_point(0.0, 0.0));
+    /// assert_eq!(points[points.len() - 1], build_point(3.0, 0.0));
+    /// assert!(points.windows(2).all(|pair| pair[0].segment_to(&pair[1]).norm() <= 0.1));
+    /// ```
+    pub fn discretize(&self, size: f64) -> Vec<Point> {
+        let count = |length: f64| ((length / size).ceil() as usize).max(1);
+
+        match self {
+            Curve::Line(..) | Curve::Arc { .. } => {
+                let segments = count(self.length());
+                (0..=segments)
+                    .map(|i| self.point_at(i as f64 / segments as f64))
+                    .collect()
+            }
+            Curve::Polyline(points) => {
+                let mut discretized = vec![points[0]];
+                for pair in points.windows(2) {
+                    let segments = count(pair[0].segment_to(&pair[1]).norm());
+                    discretized.extend((1..=segments).map(|i| {
+                        let ratio = i as f64 / segments as f64;
+                        &(&pair[0] * (1.0 - ratio)) + &(&pair[1] * ratio)
+                    }));
+                }
+                discretized
+            }
+            Curve::BSpline(_) => {
+                let arc_lengths = self.arc_lengths();
+                let length = arc_lengths[arc_lengths.len() - 1].1;
+                let segments = count(length);
+
+                // The parameter of each point is interpolated in the table of the arc lengths
+                let mut sample = 0;
+                (0..=segments)
+                    .map(|i| {
+                        let target = length * i as f64 / segments as f64;
+                        while sample + 2 < arc_lengths.len() && arc_lengths[sample + 1].1 < target {
+                            sample += 1;
+                        }
+                        let ((t0, l0), (t1, l1)) = (arc_lengths[sample], arc_lengths[sample + 1]);
+                        let ratio = if l1 > l0 {
+                            ((target - l0) / (l1 - l0)).clamp(0.0, 1.0)
+                        } else {
+                            0.0
+                        };
+                        self.point_at(t0 + (t1 - t0) * ratio)
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Samples the curve finely, giving the parameters and the lengths from the start of the curve.
+    fn arc_lengths(&self) -> Vec<(f64, f64)> {
+        let samples = match self {
+            Curve::BSpline(control_points) => 64 * control_points.len().max(2),
+            _ => 256,
+        };
+
+        let mut arc_lengths = vec![(0.0, 0.0)];
+        let mut previous = self.point_at(0.0);
+        for i in 1..=samples {
+            let t = i as f64 / samples as f64;
+            let point = self.point_at(t);
+            let length = arc_lengths[i - 1].1 + previous.segment_to(&point).norm();
+            arc_lengths.push((t, length));
+            previous = point;
+        }
+
+        arc_lengths
+    }
+}
+
+/// Evaluates a clamped B-spline of degree at most 3 with the de Boor algorithm.
+fn de_boor(control_points: &[Point], t: f64) -> Point {
+    let degree = 3.min(control_points.len() - 1);
+    let spans = control_points.len() - degree;
+    let knot = |i: usize| (i.saturating_sub(degree)).min(spans) as f64;
+
+    let u = t.clamp(0.0, 1.0) * spans as f64;
+    let span = (u.floor() as usize).min(spans - 1) + degree;
+
+    let mut points: Vec<Point> = (0..=degree)
+        .map(|j| control_points[j + span - degree])
+        .collect();
+    for r in 1..=degree {
+        for j in (r..=degree).rev() {
+            let (left, right) = (knot(j + span - degree), knot(j + 1 + span - r));
+            let alpha = (u - left) / (right - left);
+            points[j] = &(&points[j - 1] * (1.0 - alpha)) + &(&points[j] * alpha);
+        }
+    }
+
+    points[degree]
+}
+
+/// Gives the length of a polyline.
+pub fn polyline_length(points: &[Point]) -> f64 {
+    points
+        .windows(2)
+        .map(|pair| pair[0].segment_to(&pair[1]).norm())
+        .sum()
+}
+
+/// Redistributes points along a polyline, spaced by the local value of a size function.
+///
+/// The ends of the polyline are kept, so a closed polyline stays closed.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let line = vec![build_point(0.0, 0.0), build_point(3.0, 0.0)];
+///
+/// // Twice smaller cells on the left
+/// let points = resample(&line, |point| 0.1 + 0.1 * point.x / 3.0);
+///
+/// assert_eq!(points[0], build_point(0.0, 0.0));
+/// assert_eq!(points[points.len() - 1], build_point(3.0, 0.0));
+/// assert!(points[1].x < 0.11 && points[points.len() - 2].x > 2.75);
+/// ```
+pub fn resample<F: Fn(&Point) -> f64>(points: &[Point], size: F) -> Vec<Point> {
+    let options = ResamplingOptions {
+        corner_angle: PI,
+        max_turning_angle: f64::INFINITY,
+        min_size: 0.0,
+    };
+    resample_with(points, size, &options)
+}
+
+/// Parameters of `resample_with`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResamplingOptions {
+    /// Vertices where the polyline turns by more than this angle (in radians) are corners, kept as they are.
+    pub corner_angle: f64,
+    /// The size is reduced in curved regions, so that successive segments turn by at most this angle (in radians).
+    pub max_turning_angle: f64,
+    /// The size is never reduced below this value by the curvature.
+    pub min_size: f64,
+}
+
+impl Default for ResamplingOptions {
+    fn default() -> Self {
+        ResamplingOptions {
+            corner_angle: PI / 6.0,
+            max_turning_angle: PI / 18.0,
+            min_size: 0.0,
+        }
+    }
+}
+
+/// Redistributes points along a polyline, spaced by the local value of a size function,
+/// with more points where the polyline is curved.
+///
+/// The curvature is estimated at each vertex from the angle between its two segments.
+/// Corners split the polyline into parts resampled separately, and do not count as curved regions.
+/// The ends of the polyline are kept, so a closed polyline stays closed.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// // Square with a quarter circle of radius 0.1 instead of its north-east corner
+/// let mut points = vec![build_point(0.0, 0.0), build_point(1.0, 0.0)];
+/// points.extend((0..=100).map(|i| {
+///     let angle = std::f64::consts::FRAC_PI_2 * i as f64 / 100.0;
+///     build_point(0.9 + 0.1 * angle.cos(), 0.9 + 0.1 * angle.sin())
+/// }));
+/// points.extend([build_point(0.0, 1.0), build_point(0.0, 0.0)]);
+///
+/// let resampled = resample_with(&points, |_| 0.25, &ResamplingOptions::default());
+///
+/// // The corners are kept, and the quarter circle gets more points than its length requires
+/// assert!(resampled.contains(&build_point(1.0, 0.0)));
+/// assert!(resampled.contains(&build_point(0.0, 1.0)));
+/// let in_circle = resampled
+///     .iter()
+///     .filter(|point| point.x > 0.9 && point.y > 0.9)
+///     .count();
+/// assert!(in_circle >= 8);
+/// ```
+pub fn resample_with<F: Fn(&Point) -> f64>(
+    points: &[Point],
+    size: F,
+    options: &ResamplingOptions,
+) -> Vec<Point> {
+    if points.len() < 2 {
+        return points.to_vec();
+    }
+
+    let last = points.len() - 1;
+    let closed = points[0] == points[last];
+
+    // Turning angle and curvature at each vertex, the ends of an open polyline being straight
+    let mut turning = vec![0.0; points.len()];
+    let mut curvatures = vec![0.0; points.len()];
+    for i in 0..=last {
+        let (previous, next) = if i > 0 && i < last {
+            (points[i - 1], points[i + 1])
+        } else if closed {
+            (points[last - 1], points[1])
+        } else {
+            continue;
+        };
+        let (before, after) = (previous.segment_to(&points[i]), points[i].segment_to(&next));
+        let cross = before.x * after.y - before.y * after.x;
+        turning[i] = cross.atan2(&before * &after).abs();
+        let length = 0.5 * (before.norm() + after.norm());
+        if length > 0.0 {
+            curvatures[i] = turning[i] / length;
+        }
+    }
+
+    let mut corners: Vec<usize> = (1..last)
+        .filter(|&i| turning[i] > options.corner_angle)
+        .collect();
+    for &corner in &corners {
+        curvatures[corner] = 0.0;
+    }
+    corners.insert(0, 0);
+    corners.push(last);
+
+    let mut resampled = vec![points[0]];
+    for part in corners.windows(2) {
+        let part_points = resample_part(
+            &points[part[0]..=part[1]],
+            &curvatures[part[0]..=part[1]],
+            &size,
+            options,
+        );
+        resampled.extend(part_points.into_iter().skip(1));
+    }
+
+    resampled
+}
+
+/// Resamples a polyline without corners, given the curvature at its vertices.
+fn resample_part<F: Fn(&Point) -> f64>(
+    points: &[Point],
+    curvatures: &[f64],
+    size: &F,
+    options: &ResamplingOptions,
+) -> Vec<Point> {
+    // Each segment of the polyline is sampled to integrate the number of cells along it
+    const SAMPLES: usize = 8;
+    let mut samples = vec![(points[0], curvatures[0])];
+    for i in 0..points.len() - 1 {
+        samples.extend((1..=SAMPLES).map(|k| {
+            let ratio = k as f64 / SAMPLES as f64;
+            (
+                &(&points[i] * (1.0 - ratio)) + &(&points[i + 1] * ratio),
+                curvatures[i] * (1.0 - ratio) + curvatures[i + 1] * ratio,
+            )
+        }));
+    }
+
+    let local_size = |(point, curvature): &(Point, f64)| {
+        size(point)
+            .min(options.max_turning_angle / curvature)
+            .max(options.min_size)
+    };
+
+    let mut cells = vec![0.0];
+    for pair in samples.windows(2) {
+        let length = pair[0].0.segment_to(&pair[1].0).norm();
+        let density = 0.5 * (1.0 / local_size(&pair[0]) + 1.0 / local_size(&pair[1]));
+        cells.push(cells[cells.len() - 1] + length * density);
+    }
+
+    let total = cells[cells.len() - 1];
+    let count = (total.round() as usize).max(1);
+
+    let mut resampled = vec![points[0]];
+    let mut sample = 0;
+    for i in 1..count {
+        let target = total * i as f64 / count as f64;
+        while cells[sample + 1] < target {
+            sample += 1;
+        }
+        let ratio = (target - cells[sample]) / (cells[sample + 1] - cells[sample]);
+        resampled.push(&(&samples[sample].0 * (1.0 - ratio)) + &(&samples[sample + 1].0 * ratio));
+    }
+    resampled.push(points[points.len() - 1]);
+
+    resampled
+}
+
+/// Segment of a PSLG, between two of its points.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    pub start: usize,
+    pub end: usize,
+    pub tag: String,
+}
+
+/// Planar straight-line graph: the points and segments that the mesh must conform to.
+#[derive(Debug, Clone, Default)]
+pub struct Pslg {
+    pub points: Vec<Point>,
+    pub segments: Vec<Segment>,
+}
+
+impl Pslg {
+    /// Adds a chain of segments through the points, all with the same tag.
+    ///
+    /// The ends of the chain are merged with the points of the PSLG closer than the tolerance,
+    /// so that chains can be joined, or closed by ending on their first point.
+    pub fn add_chain(&mut self, points: &[Point], tag: &str, tolerance: f64) {
+        if points.len() < 2 {
+            return;
+        }
+
+        let last = points.len() - 1;
+        let first_id = self.find_or_push(&points[0], tolerance);
+        let mut previous_id = first_id;
+
+        for (i, point) in points.iter().enumerate().skip(1) {
+            let id = if i == last {
+                self.find_or_push(point, tolerance)
+            } else {
+                self.points.push(*point);
+                self.points.len() - 1
+            };
+
+            if id != previous_id {
+                self.segments.push(Segment {
+                    start: previous_id,
+                    end: id,
+                    tag: tag.to_string(),
+                });
+            }
+            previous_id = id;
+        }
+    }
+
+    /// Gives the index of a point closer than the tolerance, adding the point if there is none.
+    fn find_or_push(&mut self, point: &Point, tolerance: f64) -> usize {
+        match self
+            .points
+            .iter()
+            .position(|existing| existing.segment_to(point).norm() <= tolerance)
+        {
+            Some(id) => id,
+            None => {
+                self.points.push(*point);
+                self.points.len() - 1
+            }
+        }
+    }
+}
+
+/// Builds a PSLG from curves, discretised with a target size.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let pslg = PslgBuilder::new(0.5)
+///     .line(build_point(0.0, 0.0), build_point(2.0, 0.0), "wall")
+///     .arc(build_point(1.0, 0.0), 1.0, 0.0, std::f64::consts::PI, "farfield")
+///     .size(0.1)
+///     .circle(build_point(1.0, 0.5), 0.1, "cylinder")
+///     .build();
+///
+/// assert_eq!(pslg.segments.iter().filter(|segment| segment.tag == "wall").count(), 4);
+/// assert_eq!(pslg.segments.iter().filter(|segment| segment.tag == "farfield").count(), 7);
+/// assert_eq!(pslg.segments.iter().filter(|segment| segment.tag == "cylinder").count(), 7);
+/// // The arc ends on the line, and the circle on itself
+/// assert_eq!(pslg.points.len(), pslg.segments.len());
+/// ```
+#[derive(Debug, Clone)]
+pub struct PslgBuilder {
+    size: f64,
+    pslg: Pslg,
+}
+
+impl PslgBuilder {
+    pub fn new(size: f64) -> Self {
+        PslgBuilder {
+            size,
+            pslg: Pslg::default(),
+        }
+    }
+
+    /// Changes the target size of the next curves.
+    pub fn size(mut self, size: f64) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn curve(mut self, curve: &Curve, tag: &str) -> Self {
+        let points = curve.discretize(self.size);
+        self.pslg.add_chain(&points, tag, 1e-9 * self.size);
+        self
+    }
+
+    pub fn line(self, start: Point, end: Point, tag: &str) -> Self {
+        self.curve(&Curve::Line(start, end), tag)
+    }
+
+    /// Adds an arc of circle, from the angle `start` to the angle `end` (in radians).
+    pub fn arc(self, center: Point, radius: f64, start: f64, end: f64, tag: &str) -> Self {
+        self.curve(
+            &Curve::Arc {
+                center,
+                radius,
+                start,
+                end,
+            },
+            tag,
+        )
+    }
+
+    pub fn circle(self, center: Point, radius: f64, tag: &str) -> Self {
+        self.curve(&Curve::circle(center, radius), tag)
+    }
+
+    pub fn spline(self, control_points: Vec<Point>, tag: &str) -> Self {
+        self.curve(&Curve::BSpline(control_points), tag)
+    }
+
+    pub fn polyline(self, points: Vec<Point>, tag: &str) -> Self {
+        self.curve(&Curve::Polyline(points), tag)
+    }
+
+    pub fn build(self) -> Pslg {
+        self.pslg
+    }
+}
+
+/// Triangulation of the domain bounded by a PSLG, with its boundary edges tagged.
+#[derive(Debug, Clone, Default)]
+pub struct PslgMesh {
+    pub triangles: Vec<Triangle>,
+    pub boundaries: Vec<BoundaryFace>,
+}
+
+/// Triangulates the domain bounded by the segments of a PSLG, with additional points inside it.
+///
+/// The Delaunay triangulation of all the points is made to contain every segment by flipping edges.
+/// The triangles are then kept if they are inside an odd number of closed chains of segments,
+/// so that bodies inside a farfield boundary are holes.
+/// The additional points must not be on the segments.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let pslg = PslgBuilder::new(0.1)
+///     .polyline(
+///         vec![
+///             build_point(0.0, 0.0),
+///             build_point(2.0, 0.0),
+///             build_point(2.0, 1.0),
+///             build_point(0.0, 1.0),
+///             build_point(0.0, 0.0),
+///         ],
+///         "farfield",
+///     )
+///     .circle(build_point(1.0, 0.5), 0.25, "cylinder")
+///     .build();
+/// let points: Vec<Point> = (1..20)
+///     .flat_map(|i| (1..10).map(move |j| build_point(i as f64 / 10.0 + 0.05, j as f64 / 10.0)))
+///     .filter(|point| point.segment_to(&build_point(1.0, 0.5)).norm() > 0.3)
+///     .collect();
+///
+/// let mesh = triangulate_pslg(&pslg, &points).unwrap();
+///
+/// let area: f64 = mesh.triangles.iter().map(|triangle| triangle.signed_area()).sum();
+/// let cylinder_area = 8.0 * 0.25 * 0.25 * (std::f64::consts::PI / 8.0).sin();
+/// assert!((area - (2.0 - cylinder_area)).abs() < 1e-12);
+/// assert_eq!(mesh.boundaries.len(), 60 + 16);
+/// ```
+pub fn triangulate_pslg(pslg: &Pslg, points: &[Point]) -> Result<PslgMesh, MeshError> {
+    let mut vertices = pslg.points.clone();
+    vertices.extend_from_slice(points);
+
+    let mut triangles = triangulate(&vertices)?;
+    for segment in &pslg.segments {
+        recover_edge(
+            &pslg.points[segment.start],
+            &pslg.points[segment.end],
+            &mut triangles,
+        )?;
+    }
+
+    let tags: HashMap<_, &String> = pslg
+        .segments
+        .iter()
+        .map(|segment| {
+            (
+                edge_key(&pslg.points[segment.start], &pslg.points[segment.end]),
+                &segment.tag,
+            )
+        })
+        .collect();
+
+    // Number of segments crossed to reach each triangle from outside, the closest triangles first
+    let mut depths: Vec<Option<usize>> = vec![None; triangles.len()];
+    let mut queue = VecDeque::new();
+    for (id, triangle) in triangles.iter().enumerate() {
+        for edge in 0..3 {
+            if !matches!(triangle.adjacencies[edge], Some(Neighbor::Cell(_))) {
+                let key = edge_key(&triangle.vertices[edge], &triangle.vertices[(edge + 1) % 3]);
+                let depth = usize::from(tags.contains_key(&key));
+                if depth == 0 {
+                    queue.push_front((id, depth));
+                } else {
+                    queue.push_back((id, depth));
+                }
+            }
+        }
+    }
+    while let Some((id, depth)) = queue.pop_front() {
+        if depths[id].is_some() {
+            continue;
+        }
+        depths[id] = Some(depth);
+
+        let triangle = &triangles[id];
+        for edge in 0..3 {
+            if let Some(Neighbor::Cell(neighbor)) = triangle.adjacencies[edge] {
+                let key = edge_key(&triangle.vertices[edge], &triangle.vertices[(edge + 1) % 3]);
+                if depths[neighbor].is_some() {
+                    continue;
+                }
+                if tags.contains_key(&key) {
+                    queue.push_back((neighbor, depth + 1));
+                } else {
+                    queue.push_front((neighbor, depth));
+                }
+            }
+        }
+    }
+
+    let mut new_ids = vec![None; triangles.len()];
+    let mut kept = Vec::new();
+    for (id, triangle) in triangles.iter().enumerate() {
+        if depths[id].is_some_and(|depth| depth % 2 == 1) {
+            new_ids[id] = Some(kept.len());
+            kept.push(*triangle);
+        }
+    }
+
+    let mut boundaries = Vec::new();
+    for (id, triangle) in kept.iter_mut().enumerate() {
+        for edge in 0..3 {
+            triangle.adjacencies[edge] = match triangle.adjacencies[edge] {
+                Some(Neighbor::Cell(neighbor)) if new_ids[neighbor].is_some() => {
+                    new_ids[neighbor].map(Neighbor::Cell)
+                }
+                _ => Some(Neighbor::Boundary),
+            };
+
+            if triangle.adjacencies[edge] == Some(Neighbor::Boundary) {
+                let key = edge_key(&triangle.vertices[edge], &triangle.vertices[(edge + 1) % 3]);
+                if let Some(tag) = tags.get(&key) {
+                    boundaries.push(BoundaryFace {
+                        cell: id,
+                        edge,
+                        tag: tag.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(PslgMesh {
+        triangles: kept,
+        boundaries,
+    })
+}
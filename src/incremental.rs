@@ -0,0 +1,282 @@
+//! Triangulation kept alive between modifications, for adaptive remeshing inside a running solver.
+//!
+//! Each insertion or removal reports the cells it changed, so that the fields of the solver only need to be
+//! interpolated on these cells.
+
+use std::collections::HashMap;
+
+use crate::error::*;
+use crate::triangulation::*;
+use crate::types::*;
+use crate::validation::point_key;
+
+/// Index of a vertex of a `Triangulation`, which stays valid until the vertex is removed.
+pub type VertexId = usize;
+
+/// Index of a triangle of a `Triangulation`, which is only valid until the next modification.
+pub type CellId = usize;
+
+/// Cells changed by a modification of a `Triangulation`.
+///
+/// Every cell which is not deleted, created or moved keeps its index.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ChangeSet {
+    /// Cells which no longer exist, with their indices before the modification.
+    pub deleted: Vec<CellId>,
+    /// New cells, with their indices after the modification.
+    pub created: Vec<CellId>,
+    /// Unchanged cells given another index, as (index before, index after).
+    pub moved: Vec<(CellId, CellId)>,
+}
+
+/// Delaunay triangulation to which vertices can be added and from which they can be removed.
+#[derive(Debug, Clone)]
+pub struct Triangulation {
+    triangles: Vec<Triangle>,
+    vertices: Vec<Option<Point>>,
+    ids: HashMap<(u64, u64), VertexId>,
+    /// Distance under which a point is on a vertex or an edge, in the coordinates of the mesh.
+    tolerance: f64,
+    duplicate_policy: DuplicatePolicy,
+    kernel: InsertionKernel,
+    /// Cell close to the last modification, from which the point location starts.
+    hint: CellId,
+}
+
+/// Builds a triangulation from a Delaunay mesh, such as the one given by `triangulate`.
+///
+/// The vertices are numbered in the order given by `unique_vertices`. The tolerance of the options is relative to
+/// the largest side of the bounding box of the mesh.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let vertices: Vec<Point> = (0..5)
+///     .flat_map(|i| (0..5).map(move |j| build_point(i as f64, j as f64)))
+///     .collect();
+/// let mut triangulation =
+///     build_triangulation(triangulate(&vertices).unwrap(), &TriangulationOptions::default()).unwrap();
+///
+/// // The solver keeps one value per cell
+/// let mut field: Vec<f64> = (0..triangulation.triangles().len()).map(|i| i as f64).collect();
+///
+/// let (vertex, changes) = triangulation.insert_point(&build_point(1.3, 2.6)).unwrap();
+/// assert!(changes.created.len() >= 3);
+/// field.resize(triangulation.triangles().len(), 0.0);
+/// for &cell in &changes.created {
+///     field[cell] = -1.0;
+/// }
+///
+/// let changes = triangulation.remove_point(vertex).unwrap();
+/// for &(from, to) in &changes.moved {
+///     field[to] = field[from];
+/// }
+/// field.truncate(triangulation.triangles().len());
+/// for &cell in &changes.created {
+///     field[cell] = -2.0;
+/// }
+///
+/// assert_eq!(field.len(), 32);
+/// assert!(field.iter().all(|value| *value != -1.0));
+/// assert!(validate(&vertices, triangulation.triangles(), &[]).is_empty());
+/// assert_eq!(triangulation.vertex(vertex), None);
+/// ```
+pub fn build_triangulation(
+    triangles: Vec<Triangle>,
+    options: &TriangulationOptions,
+) -> Result<Triangulation, MeshError> {
+    let vertices = unique_vertices(&triangles);
+    let (min, max) = bounding_box(&vertices)?;
+    let size = (max.x - min.x).max(max.y - min.y);
+
+    Ok(Triangulation {
+        triangles,
+        ids: vertices
+            .iter()
+            .enumerate()
+            .map(|(id, vertex)| (point_key(vertex), id))
+            .collect(),
+        vertices: vertices.into_iter().map(Some).collect(),
+        tolerance: options.tolerance * size,
+        duplicate_policy: options.duplicate_policy,
+        kernel: options.kernel,
+        hint: 0,
+    })
+}
+
+impl Triangulation {
+    pub fn triangles(&self) -> &[Triangle] {
+        &self.triangles
+    }
+
+    /// Gives the position of a vertex, or `None` if it was removed.
+    pub fn vertex(&self, vertex: VertexId) -> Option<Point> {
+        self.vertices.get(vertex).copied().flatten()
+    }
+
+    /// Gives the index of the vertex at exactly this position, if any.
+    pub fn vertex_id(&self, point: &Point) -> Option<VertexId> {
+        self.ids.get(&point_key(point)).copied()
+    }
+
+    /// Finds the triangle containing a point, walking from the last modified cell.
+    pub fn locate(&self, point: &Point) -> Result<CellId, MeshError> {
+        if self.triangles.is_empty() {
+            return Err(MeshError::PointOutsideDomain { point: *point });
+        }
+
+        // The walk stops on the boundary if the domain is not convex
+        match walk_to_cell(point, &self.triangles, self.hint) {
+            Some(cell) => Ok(cell),
+            None => find_current_cell(point, &self.triangles),
+        }
+    }
+
+    /// Inserts a point inside the mesh with the kernel of the options, keeping it Delaunay.
+    ///
+    /// A point closer than the tolerance to a vertex is handled with the duplicate policy: with `Merge` or `Skip`,
+    /// the existing vertex is returned with an empty change set.
+    pub fn insert_point(&mut self, point: &Point) -> Result<(VertexId, ChangeSet), MeshError> {
+        let cell = match self.locate(point) {
+            Ok(cell) => cell,
+            Err(_) => locate_for_insertion(point, &self.triangles, self.tolerance)?,
+        };
+
+        let count = self.triangles.len();
+        let inserted = match self.kernel {
+            InsertionKernel::Flips => {
+                insert_vertex_in(point, &mut self.triangles, self.tolerance, cell)
+            }
+            InsertionKernel::BowyerWatson => {
+                insert_vertex_bowyer_watson_in(point, &mut self.triangles, self.tolerance, cell)
+            }
+        };
+
+        if let Err(MeshError::DuplicatePoint { .. }) = inserted {
+            let closest = self.triangles[cell]
+                .vertices
+                .iter()
+                .min_by(|a, b| {
+                    a.segment_to(point)
+                        .norm()
+                        .total_cmp(&b.segment_to(point).norm())
+                })
+                .filter(|vertex| vertex.segment_to(point).norm() <= self.tolerance)
+                .and_then(|vertex| self.vertex_id(vertex));
+            if let (Some(vertex), DuplicatePolicy::Merge | DuplicatePolicy::Skip) =
+                (closest, self.duplicate_policy)
+            {
+                return Ok((vertex, ChangeSet::default()));
+            }
+        }
+        inserted?;
+
+        // The triangles around the new vertex are exactly the ones created, and the old ones were in their slots.
+        // The cell where the point was found is one of them.
+        let mut created = vertex_star(point, &self.triangles, cell)?.cells;
+        created.sort_unstable();
+        let deleted = created.iter().copied().filter(|id| *id < count).collect();
+
+        let vertex = self.vertices.len();
+        self.vertices.push(Some(*point));
+        self.ids.insert(point_key(point), vertex);
+        self.hint = cell;
+
+        Ok((
+            vertex,
+            ChangeSet {
+                deleted,
+                created,
+                moved: Vec::new(),
+            },
+        ))
+    }
+
+    /// Removes a vertex, keeping the mesh Delaunay (see `remove_vertex`).
+    ///
+    /// The mesh has fewer cells afterwards, so the last cells are moved into the freed indices.
+    pub fn remove_point(&mut self, vertex: VertexId) -> Result<ChangeSet, MeshError> {
+        let point = self
+            .vertex(vertex)
+            .ok_or(MeshError::UnknownVertex { vertex })?;
+
+        let start = match self.locate(&point) {
+            Ok(cell)
+                if self.triangles[cell]
+                    .find_point_in_triangle_vertices(&point)
+                    .is_some() =>
+            {
+                cell
+            }
+            _ => self
+                .triangles
+                .iter()
+                .position(|triangle| triangle.find_point_in_triangle_vertices(&point).is_some())
+                .ok_or(MeshError::VertexNotFound { point })?,
+        };
+
+        let removal = remove_vertex_from(&point, &mut self.triangles, start)?;
+
+        self.vertices[vertex] = None;
+        self.ids.remove(&point_key(&point));
+        self.hint = removal.created.first().copied().unwrap_or(0);
+
+        let mut deleted = removal.star;
+        deleted.sort_unstable();
+        let mut created = removal.created;
+        created.sort_unstable();
+
+        Ok(ChangeSet {
+            deleted,
+            created,
+            moved: removal.moved,
+        })
+    }
+}
+
+/// Finds the triangle containing a point by walking from a starting triangle, each time across an edge having
+/// the point on its outer side.
+///
+/// Returns `None` if the walk reaches the boundary, for a point outside of the mesh or in a non-convex mesh,
+/// or if it does not end, which can happen in a mesh which is not Delaunay.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let vertices: Vec<Point> = (0..100)
+///     .map(|i| build_point((i as f64 * 0.7).sin(), (i as f64 * 1.3).cos()))
+///     .collect();
+/// let triangles = triangulate(&vertices).unwrap();
+/// let point = build_point(0.1, -0.2);
+///
+/// let cell = walk_to_cell(&point, &triangles, 0).unwrap();
+///
+/// assert!(triangles[cell].include(&point));
+/// assert_eq!(walk_to_cell(&build_point(5.0, 0.0), &triangles, 0), None);
+/// ```
+pub fn walk_to_cell(point: &Point, triangles: &[Triangle], start: CellId) -> Option<CellId> {
+    let mut current = start.min(triangles.len().checked_sub(1)?);
+
+    for _ in 0..triangles.len() {
+        let triangle = &triangles[current];
+        let outside = (0..3).find(|&edge| {
+            orientation(
+                &triangle.vertices[edge],
+                &triangle.vertices[(edge + 1) % 3],
+                point,
+            ) < 0.0
+        });
+
+        match outside.map(|edge| triangle.adjacencies[edge]) {
+            None => return Some(current),
+            Some(Some(Neighbor::Cell(id))) => current = id,
+            Some(_) => return None,
+        }
+    }
+
+    None
+}
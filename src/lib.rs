@@ -6,10 +6,46 @@
 //! This is only WIP and don't hesitate to reach out if you have any advices on the algorithm or on the rust part.
 //!
 
+pub use adaptation::*;
+pub use airfoil::*;
+pub use boundary_layer::*;
+pub use cell_tree::*;
+pub use divide_and_conquer::*;
+pub use error::*;
 pub use gen_vertices::*;
+pub use geometry::*;
+pub use incremental::*;
+pub use metric::*;
+pub use mixed_mesh::*;
+#[cfg(feature = "parallel")]
+pub use parallel::*;
+pub use recombination::*;
+pub use smoothing::*;
+pub use structured::*;
+pub use transfer::*;
 pub use triangulation::*;
 pub use types::*;
+pub use validation::*;
+pub use voronoi::*;
 
+pub mod adaptation;
+pub mod airfoil;
+pub mod boundary_layer;
+pub mod cell_tree;
+pub mod divide_and_conquer;
+pub mod error;
 pub mod gen_vertices;
+pub mod geometry;
+pub mod incremental;
+pub mod metric;
+pub mod mixed_mesh;
+#[cfg(feature = "parallel")]
+pub mod parallel;
+pub mod recombination;
+pub mod smoothing;
+pub mod structured;
+pub mod transfer;
 pub mod triangulation;
 pub mod types;
+pub mod validation;
+pub mod voronoi;
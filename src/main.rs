@@ -3,40 +3,35 @@
 use flo_canvas::*;
 use flo_draw::*;
 use std::io;
-use std::time;
 
-use gen_vertices::*;
-use triangulation::*;
-use types::*;
-
-mod gen_vertices;
-mod triangulation;
-mod types;
+use meshing::*;
 
 fn main() {
     with_2d_graphics(|| {
-        let time_step = time::Duration::from_millis(100);
-
-        let mut vertices = place_vertices(3);
+        let vertices = place_vertices(3);
 
         println!("{:?}", vertices.len());
 
-        let (min, max) = rescale_vertices(&mut vertices);
-
-        let mut mesh: Vec<Triangle> = Vec::new();
-
-        let mut big_triangle = build_triangle(
-            [
-                build_point(-100.0, -100.0),
-                build_point(100.0, 0.0),
-                build_point(0.0, 100.0),
-            ],
-            [None; 3],
-        );
-
-        mesh.push(big_triangle.clone());
-
-        let mut current_triangle = 0;
+        let window_dimension = match bounding_box(&vertices) {
+            Ok(dimension) => dimension,
+            Err(error) => {
+                println!("Invalid point cloud: {}", error);
+                return;
+            }
+        };
+
+        #[cfg(feature = "parallel")]
+        let mesh = triangulate_parallel(&vertices);
+        #[cfg(not(feature = "parallel"))]
+        let mesh = triangulate(&vertices);
+
+        let mesh = match mesh {
+            Ok(mesh) => mesh,
+            Err(error) => {
+                println!("Triangulation failed: {}", error);
+                return;
+            }
+        };
 
         let canvas = create_drawing_window("Meshing");
 
@@ -44,42 +39,6 @@ fn main() {
         io::stdin().read_line(&mut _dummy).expect("Error in read");
 
         let line_color = Color::Rgba(0.0, 0.0, 0.0, 1.0);
-        let window_dimension = (min, max);
-        //let window_dimension = (build_coordinates(-100.0, -100.0), build_coordinates(100.0, 100.0));
-
-        let mut counter = 0;
-
-        for point in &vertices {
-            //println!("Current point : ({:?}, {:?})", point.x, point.y);
-            current_triangle = find_current_cell(point, &mesh).expect("No triangle found");
-
-            let mut stack = insert_triangles(point, &mut mesh, current_triangle);
-
-            // canvas.draw(|gc| { gc.clear_canvas(Color::Rgba(1.0, 1.0, 1.0, 1.0)) });
-
-            // for triangle in &triangles {
-            //     triangle.draw(&window_dimension, &canvas, &line_color);
-            // }
-
-            //thread::sleep(time_step);
-
-            deal_with_delaunay_condition(&mut stack, &mut mesh, point);
-
-            // canvas.draw(|gc| { gc.clear_canvas(Color::Rgba(1.0, 1.0, 1.0, 1.0)) });
-
-            // for triangle in &triangles {
-            //     triangle.draw(&window_dimension, &canvas, &line_color);
-            // }
-
-            //thread::sleep(time_step);
-
-            println!("{:?}", counter);
-            counter += 1;
-        }
-
-        remove_big_triangle(&mut mesh, &big_triangle);
-
-        scale_back(&mut vertices, &mut mesh, (&min, &max));
 
         canvas.draw(|gc| gc.clear_canvas(Color::Rgba(1.0, 1.0, 1.0, 1.0)));
 
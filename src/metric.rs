@@ -0,0 +1,684 @@
+//! Anisotropic adaptation of a triangulation to a Riemannian metric field.
+//!
+//! The metric gives at each point the lengths wanted for the edges, in each direction: the mesh is adapted until its
+//! edges have a length close to 1 when measured with the metric. Edges are split, collapsed and flipped,
+//! and the vertices are smoothed, as in the usual metric-based remeshers.
+
+use std::collections::HashMap;
+use std::f64::consts::{FRAC_1_SQRT_2, SQRT_2};
+
+use crate::error::*;
+use crate::incremental::walk_to_cell;
+use crate::smoothing::{vertex_stars, VertexStar};
+use crate::triangulation::*;
+use crate::types::*;
+use crate::validation::point_key;
+
+/// Maximum number of passes over the edges to flip.
+const MAX_FLIP_PASSES: usize = 20;
+
+/// Relative increase of the quality needed to flip an edge, so that nearly equivalent flips are not done back and forth.
+const FLIP_MARGIN: f64 = 1e-6;
+
+/// Sine of the angle under which the boundary is considered straight at a vertex, which can then be removed.
+const STRAIGHT_TOLERANCE: f64 = 1e-10;
+
+/// Symmetric positive definite tensor `[[xx, xy], [xy, yy]]`, measuring the length of a vector `v`
+/// as `sqrt(v^T M v)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Metric {
+    pub xx: f64,
+    pub xy: f64,
+    pub yy: f64,
+}
+
+/// Creates a metric from its components.
+pub fn build_metric(xx: f64, xy: f64, yy: f64) -> Metric {
+    Metric { xx, xy, yy }
+}
+
+/// Creates a metric asking for edges of the same size in every direction.
+pub fn build_isotropic_metric(size: f64) -> Metric {
+    build_metric(1.0 / (size * size), 0.0, 1.0 / (size * size))
+}
+
+/// Creates a metric asking for edges of `size_along` along the direction, and of `size_across` across it.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let metric = build_anisotropic_metric(&build_vector(1.0, 1.0), 0.5, 0.01);
+///
+/// assert!((metric.length(&build_vector(0.5, 0.5)) - 2.0_f64.sqrt()).abs() < 1e-12);
+/// assert!((metric.length(&build_vector(-0.01, 0.01)) - 2.0_f64.sqrt()).abs() < 1e-12);
+/// ```
+pub fn build_anisotropic_metric(direction: &Vector, size_along: f64, size_across: f64) -> Metric {
+    let along = direction.normalize();
+    let across = along.orthogonal_vector();
+    let (lambda_along, lambda_across) = (
+        1.0 / (size_along * size_along),
+        1.0 / (size_across * size_across),
+    );
+
+    build_metric(
+        lambda_along * along.x * along.x + lambda_across * across.x * across.x,
+        lambda_along * along.x * along.y + lambda_across * across.x * across.y,
+        lambda_along * along.y * along.y + lambda_across * across.y * across.y,
+    )
+}
+
+impl Metric {
+    /// Scalar product of two vectors with the metric.
+    pub fn dot(&self, first: &Vector, second: &Vector) -> f64 {
+        first.x * (self.xx * second.x + self.xy * second.y)
+            + first.y * (self.xy * second.x + self.yy * second.y)
+    }
+
+    /// Length of a vector with the metric.
+    pub fn length(&self, vector: &Vector) -> f64 {
+        self.dot(vector, vector).sqrt()
+    }
+
+    pub fn determinant(&self) -> f64 {
+        self.xx * self.yy - self.xy * self.xy
+    }
+
+    pub fn is_positive_definite(&self) -> bool {
+        self.xx > 0.0 && self.determinant() > 0.0
+    }
+
+    /// Quality of a triangle measured with the metric, 1 for a triangle equilateral in the metric,
+    /// and negative for a clockwise triangle.
+    pub fn triangle_quality(&self, triangle: &Triangle) -> f64 {
+        let [a, b, c] = &triangle.vertices;
+        let squares: f64 = [a.segment_to(b), b.segment_to(c), c.segment_to(a)]
+            .iter()
+            .map(|edge| self.dot(edge, edge))
+            .sum();
+        let area = orientation(a, b, c) / 2.0 * self.determinant().sqrt();
+
+        4.0 * 3.0_f64.sqrt() * area / squares
+    }
+}
+
+/// Metric given at every point of the domain.
+pub trait MetricField {
+    fn metric_at(&self, point: &Point) -> Metric;
+}
+
+impl<F: Fn(&Point) -> Metric> MetricField for F {
+    fn metric_at(&self, point: &Point) -> Metric {
+        self(point)
+    }
+}
+
+/// Metric given at the vertices of a background mesh, and interpolated linearly in its triangles.
+#[derive(Debug, Clone)]
+pub struct VertexMetrics {
+    triangles: Vec<Triangle>,
+    vertices: Vec<Point>,
+    metrics: Vec<Metric>,
+    ids: HashMap<(u64, u64), usize>,
+}
+
+/// Builds a metric field from one metric per vertex of a mesh, in the order given by `unique_vertices`.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let vertices = vec![
+///     build_point(0.0, 0.0),
+///     build_point(1.0, 0.0),
+///     build_point(1.0, 1.0),
+///     build_point(0.0, 1.0),
+/// ];
+/// let triangles = triangulate(&vertices).unwrap();
+/// let metrics: Vec<Metric> = unique_vertices(&triangles)
+///     .iter()
+///     .map(|vertex| build_isotropic_metric(0.1 + vertex.x))
+///     .collect();
+///
+/// let field = build_vertex_metrics(&triangles, &metrics).unwrap();
+///
+/// assert_eq!(field.metric_at(&build_point(0.0, 1.0)), build_isotropic_metric(0.1));
+/// assert!((field.metric_at(&build_point(0.5, 0.5)).xx - (100.0 + 1.0 / 1.21) / 2.0).abs() < 1e-12);
+/// ```
+pub fn build_vertex_metrics(
+    triangles: &[Triangle],
+    metrics: &[Metric],
+) -> Result<VertexMetrics, MeshError> {
+    let vertices = unique_vertices(triangles);
+    if metrics.len() != vertices.len() {
+        return Err(MeshError::FieldSizeMismatch {
+            expected: vertices.len(),
+            found: metrics.len(),
+        });
+    }
+    if let Some((vertex, _)) = vertices
+        .iter()
+        .zip(metrics)
+        .find(|(_, metric)| !metric.is_positive_definite())
+    {
+        return Err(MeshError::InvalidMetric { point: *vertex });
+    }
+
+    Ok(VertexMetrics {
+        triangles: triangles.to_vec(),
+        ids: vertices
+            .iter()
+            .enumerate()
+            .map(|(id, vertex)| (point_key(vertex), id))
+            .collect(),
+        vertices,
+        metrics: metrics.to_vec(),
+    })
+}
+
+impl MetricField for VertexMetrics {
+    /// Interpolates the metrics of the vertices of the triangle containing the point.
+    /// Outside of the mesh, the metric of the closest vertex is used.
+    fn metric_at(&self, point: &Point) -> Metric {
+        let cell = walk_to_cell(point, &self.triangles, 0)
+            .or_else(|| find_current_cell(point, &self.triangles).ok());
+
+        match cell {
+            Some(id) => {
+                let triangle = &self.triangles[id];
+                let (s, t) = triangle.barycentric_coordinates_from(point);
+                let [first, second, third] = triangle
+                    .vertices
+                    .map(|vertex| self.metrics[self.ids[&point_key(&vertex)]]);
+                let weights = [s, t, 1.0 - s - t];
+                build_metric(
+                    weights[0] * first.xx + weights[1] * second.xx + weights[2] * third.xx,
+                    weights[0] * first.xy + weights[1] * second.xy + weights[2] * third.xy,
+                    weights[0] * first.yy + weights[1] * second.yy + weights[2] * third.yy,
+                )
+            }
+            None => {
+                let closest = (0..self.vertices.len())
+                    .min_by(|a, b| {
+                        let (to_a, to_b) = (
+                            point.segment_to(&self.vertices[*a]),
+                            point.segment_to(&self.vertices[*b]),
+                        );
+                        (&to_a * &to_a).total_cmp(&(&to_b * &to_b))
+                    })
+                    .unwrap_or(0);
+                self.metrics[closest]
+            }
+        }
+    }
+}
+
+/// Parameters of the metric-based adaptation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetricAdaptationOptions {
+    /// Maximum number of passes of splits, collapses, flips and smoothing.
+    pub iterations: usize,
+    /// No edge is split once the mesh has this number of cells.
+    pub max_cells: usize,
+}
+
+impl Default for MetricAdaptationOptions {
+    fn default() -> Self {
+        MetricAdaptationOptions {
+            iterations: 20,
+            max_cells: 1_000_000,
+        }
+    }
+}
+
+/// Adapts a triangulation to a metric field, so that its edges have a length close to 1 in the metric.
+///
+/// Each pass splits at their middle the edges longer than `sqrt(2)`, collapses the edges shorter than `1 / sqrt(2)`
+/// into one of their vertices, flips the edges improving the quality measured with the metric
+/// (see `Metric::triangle_quality`) and smooths the vertices towards a unit distance to their neighbors.
+/// The passes stop when no edge is split or collapsed anymore.
+///
+/// The vertices on the boundary are never moved, and are only removed where the boundary is straight,
+/// so that the domain is kept. The flips follow the metric, so the mesh is usually not Delaunay anymore.
+/// The metric is checked to be positive definite at the vertices of the input mesh.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let vertices: Vec<Point> = (0..=5)
+///     .flat_map(|i| (0..=5).map(move |j| build_point(i as f64 / 5.0, j as f64 / 5.0)))
+///     .collect();
+/// let mut triangles = triangulate(&vertices).unwrap();
+///
+/// // Shear layer along y = 0.5, with cells stretched along x
+/// let metric = |point: &Point| {
+///     build_anisotropic_metric(&build_vector(1.0, 0.0), 0.1, 0.01 + 0.2 * (point.y - 0.5).abs())
+/// };
+///
+/// adapt_to_metric(&mut triangles, &metric, &MetricAdaptationOptions::default()).unwrap();
+///
+/// // The flips follow the metric, so the mesh is not Delaunay anymore
+/// assert!(validate(&unique_vertices(&triangles), &triangles, &[])
+///     .iter()
+///     .all(|issue| matches!(issue, MeshIssue::NotDelaunay { .. })));
+///
+/// // Most edges have a length close to 1 in the metric
+/// let lengths: Vec<f64> = triangles
+///     .iter()
+///     .flat_map(|triangle| (0..3).map(move |i| (triangle.vertices[i], triangle.vertices[(i + 1) % 3])))
+///     .map(|(start, end)| metric(&(&(&start + &end) / 2.0)).length(&start.segment_to(&end)))
+///     .collect();
+/// let unit = lengths.iter().filter(|length| (0.5..2.0).contains(*length)).count();
+/// assert!(unit as f64 > 0.8 * lengths.len() as f64);
+/// ```
+pub fn adapt_to_metric<M: MetricField>(
+    triangles: &mut Vec<Triangle>,
+    metric: &M,
+    options: &MetricAdaptationOptions,
+) -> Result<(), MeshError> {
+    if let Some(vertex) = unique_vertices(triangles)
+        .into_iter()
+        .find(|vertex| !metric.metric_at(vertex).is_positive_definite())
+    {
+        return Err(MeshError::InvalidMetric { point: vertex });
+    }
+
+    for _ in 0..options.iterations {
+        let splits = split_long_edges(triangles, metric, options.max_cells)?;
+        let collapses = collapse_short_edges(triangles, metric);
+        flip_edges(triangles, metric)?;
+        smooth_vertices(triangles, metric);
+        flip_edges(triangles, metric)?;
+
+        if splits + collapses == 0 {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Length of an edge with the metric at its middle.
+fn edge_length<M: MetricField>(metric: &M, start: &Point, end: &Point) -> f64 {
+    metric
+        .metric_at(&(&(start + end) / 2.0))
+        .length(&start.segment_to(end))
+}
+
+fn quality<M: MetricField>(metric: &M, triangle: &Triangle) -> f64 {
+    metric
+        .metric_at(&triangle.center())
+        .triangle_quality(triangle)
+}
+
+/// Splits the edges too long in the metric at their middle, the longest first.
+/// An edge whose triangle was already changed in the pass is left for the next one.
+fn split_long_edges<M: MetricField>(
+    triangles: &mut Vec<Triangle>,
+    metric: &M,
+    max_cells: usize,
+) -> Result<usize, MeshError> {
+    let mut long_edges: Vec<(f64, usize, usize, Point, Point)> = Vec::new();
+    for (id, triangle) in triangles.iter().enumerate() {
+        for edge in 0..3 {
+            // Each interior edge is only seen from one side
+            if matches!(triangle.adjacencies[edge], Some(Neighbor::Cell(neighbor)) if neighbor < id)
+            {
+                continue;
+            }
+            let (start, end) = (triangle.vertices[edge], triangle.vertices[(edge + 1) % 3]);
+            let length = edge_length(metric, &start, &end);
+            if length > SQRT_2 {
+                long_edges.push((length, id, edge, start, end));
+            }
+        }
+    }
+    long_edges.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let mut count = 0;
+    for (_, id, edge, start, end) in long_edges {
+        if triangles.len() + 2 > max_cells {
+            break;
+        }
+        let triangle = &triangles[id];
+        if triangle.vertices[edge] != start || triangle.vertices[(edge + 1) % 3] != end {
+            continue;
+        }
+
+        insert_on_edge(&(&(&start + &end) / 2.0), triangles, id, edge)?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+/// Collapses the edges too short in the metric, the shortest first, by merging a vertex into its neighbor.
+/// The vertices around a collapsed edge are not changed again in the pass.
+fn collapse_short_edges<M: MetricField>(triangles: &mut Vec<Triangle>, metric: &M) -> usize {
+    let (stars, is_boundary) = vertex_stars(triangles, &[]);
+    let points: Vec<Point> = stars
+        .iter()
+        .map(|star| {
+            let (id, local_id) = star[0];
+            triangles[id].vertices[local_id]
+        })
+        .collect();
+    let ids: HashMap<(u64, u64), usize> = points
+        .iter()
+        .enumerate()
+        .map(|(vertex_id, point)| (point_key(point), vertex_id))
+        .collect();
+    let rings: Vec<Vec<usize>> = stars
+        .iter()
+        .map(|star| {
+            let mut ring: Vec<usize> = star
+                .iter()
+                .flat_map(|&(id, local_id)| {
+                    let vertices = &triangles[id].vertices;
+                    [(local_id + 1) % 3, (local_id + 2) % 3].map(|i| ids[&point_key(&vertices[i])])
+                })
+                .collect();
+            ring.sort_unstable();
+            ring.dedup();
+            ring
+        })
+        .collect();
+
+    let mut short_edges: Vec<(f64, usize, usize)> = Vec::new();
+    for (removed, ring) in rings.iter().enumerate() {
+        // A boundary vertex can only be merged along the boundary, where it is straight
+        let candidates = if is_boundary[removed] {
+            straight_boundary_neighbors(triangles, &stars[removed], &points[removed])
+                .map(|neighbors| {
+                    neighbors
+                        .map(|neighbor| ids[&point_key(&neighbor)])
+                        .to_vec()
+                })
+                .unwrap_or_default()
+        } else {
+            ring.clone()
+        };
+        for kept in candidates {
+            let length = edge_length(metric, &points[removed], &points[kept]);
+            if length < FRAC_1_SQRT_2 {
+                short_edges.push((length, removed, kept));
+            }
+        }
+    }
+    short_edges.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut locked = vec![false; stars.len()];
+    let mut dead = vec![false; triangles.len()];
+    let mut count = 0;
+    for (_, removed, kept) in short_edges {
+        if locked[removed] || locked[kept] {
+            continue;
+        }
+        // The other vertices of the triangles around the edge must be the only common neighbors
+        let common = rings[removed]
+            .iter()
+            .filter(|neighbor| rings[kept].binary_search(neighbor).is_ok())
+            .count();
+        if common != if is_boundary[removed] { 1 } else { 2 } {
+            continue;
+        }
+
+        if collapse_edge(
+            triangles,
+            &stars[removed],
+            &points[removed],
+            &points[kept],
+            metric,
+            &mut dead,
+        ) {
+            locked[removed] = true;
+            locked[kept] = true;
+            for &neighbor in &rings[removed] {
+                locked[neighbor] = true;
+            }
+            count += 1;
+        }
+    }
+
+    remove_dead_triangles(triangles, &dead);
+
+    count
+}
+
+/// Gives the two neighbors of a boundary vertex along the boundary, if the boundary is straight there.
+fn straight_boundary_neighbors(
+    triangles: &[Triangle],
+    star: &VertexStar,
+    vertex: &Point,
+) -> Option<[Point; 2]> {
+    let mut neighbors = Vec::new();
+    for &(id, local_id) in star {
+        let triangle = &triangles[id];
+        if !matches!(triangle.adjacencies[local_id], Some(Neighbor::Cell(_))) {
+            neighbors.push(triangle.vertices[(local_id + 1) % 3]);
+        }
+        if !matches!(
+            triangle.adjacencies[(local_id + 2) % 3],
+            Some(Neighbor::Cell(_))
+        ) {
+            neighbors.push(triangle.vertices[(local_id + 2) % 3]);
+        }
+    }
+
+    match neighbors[..] {
+        [first, second] => {
+            let (to_first, to_second) = (vertex.segment_to(&first), vertex.segment_to(&second));
+            let cross = to_first.x * to_second.y - to_first.y * to_second.x;
+            (cross.abs() <= STRAIGHT_TOLERANCE * to_first.norm() * to_second.norm())
+                .then_some([first, second])
+        }
+        _ => None,
+    }
+}
+
+/// Merges a vertex into its neighbor, if every triangle left stays counter-clockwise without new long edges.
+/// The triangles around the edge, two or one on the boundary, are only marked as dead, and nothing points to them
+/// anymore.
+fn collapse_edge<M: MetricField>(
+    triangles: &mut [Triangle],
+    star: &VertexStar,
+    removed: &Point,
+    kept: &Point,
+    metric: &M,
+    dead: &mut [bool],
+) -> bool {
+    let (around, moved): (VertexStar, VertexStar) = star.iter().partition(|(id, _)| {
+        triangles[*id]
+            .find_point_in_triangle_vertices(kept)
+            .is_some()
+    });
+    if around.is_empty() || around.len() > 2 {
+        return false;
+    }
+
+    for &(id, local_id) in &moved {
+        let mut triangle = triangles[id];
+        triangle.vertices[local_id] = *kept;
+        let [a, b, c] = &triangle.vertices;
+        if orientation(a, b, c) <= 0.0 {
+            return false;
+        }
+        for other in [(local_id + 1) % 3, (local_id + 2) % 3] {
+            if edge_length(metric, kept, &triangle.vertices[other]) > SQRT_2 {
+                return false;
+            }
+        }
+    }
+
+    for &(id, _) in &around {
+        let triangle = triangles[id];
+        let (mut removed_side, mut kept_side) = (None, None);
+        for edge in 0..3 {
+            let (start, end) = (&triangle.vertices[edge], &triangle.vertices[(edge + 1) % 3]);
+            if start == removed && end == kept || start == kept && end == removed {
+                continue;
+            }
+            if start == removed || end == removed {
+                removed_side = triangle.adjacencies[edge];
+            } else {
+                kept_side = triangle.adjacencies[edge];
+            }
+        }
+        replace_adjacency(triangles, removed_side, id, kept_side);
+        replace_adjacency(triangles, kept_side, id, removed_side);
+        dead[id] = true;
+    }
+
+    for &(id, local_id) in &moved {
+        triangles[id].vertices[local_id] = *kept;
+    }
+
+    true
+}
+
+/// Makes the neighbor point to `value` instead of the triangle `old_id`.
+fn replace_adjacency(
+    triangles: &mut [Triangle],
+    neighbor: Option<Neighbor>,
+    old_id: usize,
+    value: Option<Neighbor>,
+) {
+    if let Some(Neighbor::Cell(id)) = neighbor {
+        for adjacency in triangles[id].adjacencies.iter_mut() {
+            if *adjacency == Some(Neighbor::Cell(old_id)) {
+                *adjacency = value;
+            }
+        }
+    }
+}
+
+/// Removes the dead triangles, which no other triangle points to, and renumbers the adjacencies.
+fn remove_dead_triangles(triangles: &mut Vec<Triangle>, dead: &[bool]) {
+    let mut new_ids: Vec<usize> = Vec::with_capacity(triangles.len());
+    let mut count = 0;
+    for is_dead in dead {
+        new_ids.push(count);
+        if !is_dead {
+            count += 1;
+        }
+    }
+
+    let mut id = 0;
+    triangles.retain(|_| {
+        id += 1;
+        !dead[id - 1]
+    });
+
+    for triangle in triangles.iter_mut() {
+        for adjacency in triangle.adjacencies.iter_mut() {
+            if let Some(Neighbor::Cell(old_id)) = *adjacency {
+                *adjacency = Some(Neighbor::Cell(new_ids[old_id]));
+            }
+        }
+    }
+}
+
+/// Flips the interior edges while it increases the worst quality of the two triangles around them.
+fn flip_edges<M: MetricField>(triangles: &mut [Triangle], metric: &M) -> Result<(), MeshError> {
+    for _ in 0..MAX_FLIP_PASSES {
+        let mut flipped = false;
+
+        for id in 0..triangles.len() {
+            for edge in 0..3 {
+                let triangle = triangles[id];
+                let Some(Neighbor::Cell(opposite_id)) = triangle.adjacencies[edge] else {
+                    continue;
+                };
+                let opposite = triangles[opposite_id];
+                let Some(d_local_id) = opposite.find_point_local_id_opposite_to(id) else {
+                    continue;
+                };
+
+                // Quad (a, d, b, c) as in `flip_edge`
+                let (a, b, c, d) = (
+                    triangle.vertices[edge],
+                    triangle.vertices[(edge + 1) % 3],
+                    triangle.vertices[(edge + 2) % 3],
+                    opposite.vertices[d_local_id],
+                );
+                let (first, second) = (
+                    build_triangle([a, d, c], [None; 3]),
+                    build_triangle([d, b, c], [None; 3]),
+                );
+                if first.signed_area() <= 0.0 || second.signed_area() <= 0.0 {
+                    continue;
+                }
+
+                let before = quality(metric, &triangle).min(quality(metric, &opposite));
+                let after = quality(metric, &first).min(quality(metric, &second));
+                if after > before * (1.0 + FLIP_MARGIN) {
+                    flip_edge(triangles, id, edge)?;
+                    flipped = true;
+                }
+            }
+        }
+
+        if !flipped {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Moves each interior vertex to the average of the positions at a unit distance from its neighbors,
+/// or part of the way there, if the worst quality of the triangles around it does not decrease.
+fn smooth_vertices<M: MetricField>(triangles: &mut [Triangle], metric: &M) {
+    let (stars, is_boundary) = vertex_stars(triangles, &[]);
+
+    for (star, is_boundary) in stars.iter().zip(is_boundary) {
+        if is_boundary {
+            continue;
+        }
+        let (id, local_id) = star[0];
+        let current = triangles[id].vertices[local_id];
+
+        // Around an interior vertex, each neighbor is the next vertex of exactly one triangle
+        let targets: Vec<Point> = star
+            .iter()
+            .map(|&(id, local_id)| {
+                let neighbor = triangles[id].vertices[(local_id + 1) % 3];
+                let to_current = neighbor.segment_to(&current);
+                let length = edge_length(metric, &neighbor, &current);
+                build_point(
+                    neighbor.x + to_current.x / length,
+                    neighbor.y + to_current.y / length,
+                )
+            })
+            .collect();
+        let target = &targets
+            .iter()
+            .fold(build_point(0.0, 0.0), |sum, target| &sum + target)
+            / targets.len() as f64;
+
+        let star_quality = |position: &Point| {
+            star.iter()
+                .map(|&(id, local_id)| {
+                    let mut triangle = triangles[id];
+                    triangle.vertices[local_id] = *position;
+                    quality(metric, &triangle)
+                })
+                .fold(f64::MAX, f64::min)
+        };
+        let before = star_quality(&current);
+
+        for relaxation in [1.0, 0.5, 0.25] {
+            let position = &(&current * (1.0 - relaxation)) + &(&target * relaxation);
+            if star_quality(&position) > before {
+                for &(id, local_id) in star {
+                    triangles[id].vertices[local_id] = position;
+                }
+                break;
+            }
+        }
+    }
+}
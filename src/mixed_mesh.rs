@@ -0,0 +1,178 @@
+//! Mesh holding cells of different shapes, with adjacencies across shapes.
+//!
+//! `Neighbor::Cell(i)` always refers to the i-th cell of the mesh, whatever its shape,
+//! so the code using the mesh only goes through the `Cell` trait.
+
+use std::collections::HashMap;
+
+use crate::recombination::*;
+use crate::types::*;
+use crate::validation::point_key;
+
+/// Mesh made of any kind of cells.
+#[derive(Debug, Clone, Default)]
+pub struct MixedMesh {
+    pub cells: Vec<MeshCell>,
+}
+
+/// Edge of the mesh seen as a finite volume face, between its owner cell and a neighbor or a boundary.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Face {
+    pub owner: usize,
+    /// Local index of the edge in the owner cell.
+    pub edge: usize,
+    pub neighbor: Neighbor,
+    /// Unit normal, pointing out of the owner cell.
+    pub normal: Vector,
+    pub length: f64,
+    pub center: Point,
+}
+
+impl MixedMesh {
+    /// Creates a mesh from cells, computing their adjacencies from the edges they share.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use meshing::*;
+    ///
+    /// let (a, b, c, d, e) = (
+    ///     build_point(0.0, 0.0),
+    ///     build_point(1.0, 0.0),
+    ///     build_point(1.0, 1.0),
+    ///     build_point(0.0, 1.0),
+    ///     build_point(2.0, 0.5),
+    /// );
+    /// let mesh = MixedMesh::from_cells(vec![
+    ///     build_quad([a, b, c, d], [None; 4]).into(),
+    ///     build_triangle([b, e, c], [None; 3]).into(),
+    /// ]);
+    ///
+    /// assert_eq!(mesh.cells[0].iter_adjacencies().nth(1), Some(&Some(Neighbor::Cell(1))));
+    ///
+    /// let faces = mesh.faces();
+    /// assert_eq!(faces.len(), 6);
+    /// assert_eq!(faces.iter().filter(|face| face.neighbor == Neighbor::Boundary).count(), 5);
+    /// ```
+    pub fn from_cells(cells: Vec<MeshCell>) -> Self {
+        let mut mesh = MixedMesh { cells };
+        mesh.connect();
+        mesh
+    }
+
+    /// Adds a cell at the end of the mesh and returns its index. Adjacencies are left untouched.
+    pub fn push<T: Into<MeshCell>>(&mut self, cell: T) -> usize {
+        self.cells.push(cell.into());
+        self.cells.len() - 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Recomputes the adjacencies of every cell from the edges they share.
+    pub fn connect(&mut self) {
+        let vertices: Vec<Vec<Point>> = self
+            .cells
+            .iter()
+            .map(|cell| cell.iter_vertices().copied().collect())
+            .collect();
+        let cells: Vec<&[Point]> = vertices.iter().map(|vertices| &vertices[..]).collect();
+
+        for (cell, adjacencies) in self.cells.iter_mut().zip(connect(&cells)) {
+            cell.adjacencies_mut().copy_from_slice(&adjacencies);
+        }
+    }
+
+    /// Lists every edge of the mesh once, as needed by a finite volume solver.
+    /// An interior edge is owned by the cell with the lowest index.
+    pub fn faces(&self) -> Vec<Face> {
+        let mut faces = Vec::new();
+
+        for (owner, cell) in self.cells.iter().enumerate() {
+            let vertices: Vec<&Point> = cell.iter_vertices().collect();
+            let normals = cell.normals();
+
+            for (edge, adjacency) in cell.iter_adjacencies().enumerate() {
+                let neighbor = match adjacency {
+                    Some(Neighbor::Cell(id)) if *id < owner => continue,
+                    Some(neighbor) => *neighbor,
+                    None => Neighbor::Boundary,
+                };
+                let (start, end) = (vertices[edge], vertices[(edge + 1) % vertices.len()]);
+
+                faces.push(Face {
+                    owner,
+                    edge,
+                    neighbor,
+                    normal: normals[edge],
+                    length: start.segment_to(end).norm(),
+                    center: &(start + end) / 2.0,
+                });
+            }
+        }
+
+        faces
+    }
+
+    /// Gives the quality of each cell (see `Cell::quality`).
+    pub fn quality(&self) -> Vec<f64> {
+        self.cells.iter().map(|cell| cell.quality()).collect()
+    }
+}
+
+impl From<Vec<Triangle>> for MixedMesh {
+    fn from(triangles: Vec<Triangle>) -> Self {
+        MixedMesh {
+            cells: triangles.into_iter().map(MeshCell::from).collect(),
+        }
+    }
+}
+
+impl From<RecombinedMesh> for MixedMesh {
+    /// Keeps the adjacencies, since the recombined mesh already numbers quads first then triangles.
+    fn from(mesh: RecombinedMesh) -> Self {
+        MixedMesh {
+            cells: mesh
+                .quads
+                .into_iter()
+                .map(MeshCell::from)
+                .chain(mesh.triangles.into_iter().map(MeshCell::from))
+                .collect(),
+        }
+    }
+}
+
+/// Oriented edge, from the key of its start to the key of its end.
+type EdgeKey = ((u64, u64), (u64, u64));
+
+/// Computes the adjacencies of cells given by their counter-clockwise vertices,
+/// by matching the edges they share. Edges without neighbor are boundaries.
+pub(crate) fn connect(cells: &[&[Point]]) -> Vec<Vec<Option<Neighbor>>> {
+    let mut edges: HashMap<EdgeKey, usize> = HashMap::new();
+    for (id, vertices) in cells.iter().enumerate() {
+        for i in 0..vertices.len() {
+            let end = &vertices[(i + 1) % vertices.len()];
+            edges.insert((point_key(&vertices[i]), point_key(end)), id);
+        }
+    }
+
+    cells
+        .iter()
+        .map(|vertices| {
+            (0..vertices.len())
+                .map(|i| {
+                    let end = &vertices[(i + 1) % vertices.len()];
+                    match edges.get(&(point_key(end), point_key(&vertices[i]))) {
+                        Some(id) => Some(Neighbor::Cell(*id)),
+                        None => Some(Neighbor::Boundary),
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
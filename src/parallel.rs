@@ -0,0 +1,84 @@
+//! Parallel Delaunay triangulation, enabled by the `parallel` feature.
+//!
+//! The divide and conquer triangulation splits the points in two halves separated by a vertical line,
+//! so the triangulations of the halves are independent: each one is built on its own thread, in its own quad-edge structure.
+//! The structures are then joined and merged as in the sequential algorithm.
+
+use crate::divide_and_conquer::*;
+use crate::error::*;
+use crate::types::*;
+
+/// Number of points under which the triangulation is always built on a single thread.
+const PARALLEL_THRESHOLD: usize = 10_000;
+
+/// Triangulates a point cloud like `triangulate_divide_and_conquer`, using every thread of the rayon thread pool.
+///
+/// The triangles are the same as the ones of `triangulate_divide_and_conquer`, but they may be in another order.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let vertices: Vec<Point> = (0..50_000)
+///     .map(|i| build_point((i as f64 * 0.7).sin(), (i as f64 * 1.3).cos()))
+///     .collect();
+///
+/// let sequential = triangulate_divide_and_conquer(&vertices).unwrap();
+/// // The number of threads is the one of the current rayon thread pool
+/// let pool = rayon::ThreadPoolBuilder::new().num_threads(4).build().unwrap();
+/// let parallel = pool.install(|| triangulate_parallel(&vertices)).unwrap();
+///
+/// // Same triangles, whatever their order and the first vertex of each of them
+/// let sorted = |triangles: &[Triangle]| {
+///     let mut keys: Vec<_> = triangles
+///         .iter()
+///         .map(|triangle| {
+///             let mut key = triangle.vertices.map(|vertex| (vertex.x.to_bits(), vertex.y.to_bits()));
+///             key.sort();
+///             key
+///         })
+///         .collect();
+///     keys.sort();
+///     keys
+/// };
+/// assert_eq!(sorted(&parallel), sorted(&sequential));
+/// ```
+pub fn triangulate_parallel(vertices: &[Point]) -> Result<Vec<Triangle>, MeshError> {
+    let points = sorted_unique_points(vertices)?;
+
+    // There is one part per thread, as the joins of the quad-edge structures copy them
+    let part_size = points
+        .len()
+        .div_ceil(rayon::current_num_threads())
+        .max(PARALLEL_THRESHOLD);
+    let (edges, _) = delaunay_parallel(&points, 0, points.len(), part_size);
+
+    quad_edges_to_triangles(&edges, &points)
+}
+
+/// Triangulates the points from `start` to `end` (excluded) in a new quad-edge structure,
+/// and returns it with the hull edges returned by `delaunay`. Parts larger than `part_size` are split between two threads.
+fn delaunay_parallel(
+    points: &[Point],
+    start: usize,
+    end: usize,
+    part_size: usize,
+) -> (QuadEdges, (usize, usize)) {
+    if end - start <= part_size {
+        let mut edges = QuadEdges::default();
+        let hull = delaunay(&mut edges, points, start, end);
+        return (edges, hull);
+    }
+
+    let middle = start + (end - start) / 2;
+    let ((mut edges, left), (right_edges, (right_inner, right_outer))) = rayon::join(
+        || delaunay_parallel(points, start, middle, part_size),
+        || delaunay_parallel(points, middle, end, part_size),
+    );
+
+    let offset = edges.append(right_edges);
+    let right = (right_inner + offset, right_outer + offset);
+    let hull = merge(&mut edges, points, left, right);
+    (edges, hull)
+}
@@ -0,0 +1,188 @@
+//! Recombination of a triangulation into a quad-dominant mesh.
+//!
+//! Pairs of adjacent triangles are merged into quads, in the spirit of the Blossom-Quad algorithm,
+//! but with a greedy pairing on the quality of the resulting quads instead of a perfect matching.
+
+use crate::mixed_mesh::connect;
+use crate::types::*;
+
+/// Parameters of the recombination.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RecombinationOptions {
+    /// Two triangles are only merged if the quad they form has at least this quality (see `Cell::quality`).
+    pub min_quality: f64,
+    /// Splits every cell of the recombined mesh into quads, to get a mesh with quads only.
+    pub all_quads: bool,
+}
+
+impl Default for RecombinationOptions {
+    fn default() -> Self {
+        RecombinationOptions {
+            min_quality: 0.5,
+            all_quads: false,
+        }
+    }
+}
+
+/// Mesh made of quads and triangles.
+///
+/// Cells are numbered with the quads first, then the triangles:
+/// `Neighbor::Cell(i)` is `quads[i]` if `i < quads.len()`, and `triangles[i - quads.len()]` otherwise.
+#[derive(Debug, Clone)]
+pub struct RecombinedMesh {
+    pub quads: Vec<Quad>,
+    pub triangles: Vec<Triangle>,
+}
+
+/// Merges pairs of adjacent triangles into convex quads, the best quads being chosen first.
+///
+/// Triangles which could not be paired are kept.
+/// If `options.all_quads` is set, every quad is then split into four quads and every triangle into three,
+/// from the center of the cell and the middle of its edges.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let vertices = vec![
+///     build_point(0.0, 0.0),
+///     build_point(1.0, 0.0),
+///     build_point(1.0, 1.0),
+///     build_point(0.0, 1.0),
+/// ];
+/// let mesh = triangulate(&vertices).unwrap();
+///
+/// let recombined = recombine(&mesh, &RecombinationOptions::default());
+/// assert_eq!((recombined.quads.len(), recombined.triangles.len()), (1, 0));
+///
+/// let options = RecombinationOptions {
+///     all_quads: true,
+///     ..Default::default()
+/// };
+/// assert_eq!(recombine(&mesh, &options).quads.len(), 4);
+/// ```
+pub fn recombine(triangles: &[Triangle], options: &RecombinationOptions) -> RecombinedMesh {
+    // Every interior edge gives a candidate quad
+    let mut candidates: Vec<(f64, usize, usize, [Point; 4])> = Vec::new();
+    for (id, triangle) in triangles.iter().enumerate() {
+        for edge in 0..3 {
+            let neighbor_id = match triangle.adjacencies[edge] {
+                Some(Neighbor::Cell(neighbor_id)) if neighbor_id > id => neighbor_id,
+                _ => continue,
+            };
+            let opposite_local_id = match triangles[neighbor_id].find_point_local_id_opposite_to(id)
+            {
+                Some(local_id) => local_id,
+                None => continue,
+            };
+
+            let quad = build_quad(
+                [
+                    triangle.vertices[edge],
+                    triangles[neighbor_id].vertices[opposite_local_id],
+                    triangle.vertices[(edge + 1) % 3],
+                    triangle.vertices[(edge + 2) % 3],
+                ],
+                [None; 4],
+            );
+
+            if quad
+                .angles()
+                .iter()
+                .all(|angle| *angle < std::f64::consts::PI)
+            {
+                let quality = quad.quality();
+                if quality >= options.min_quality {
+                    candidates.push((quality, id, neighbor_id, quad.vertices));
+                }
+            }
+        }
+    }
+
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let mut paired = vec![false; triangles.len()];
+    let mut quads = Vec::new();
+    for (_, first, second, vertices) in candidates {
+        if !paired[first] && !paired[second] {
+            paired[first] = true;
+            paired[second] = true;
+            quads.push(vertices);
+        }
+    }
+
+    let mut leftovers: Vec<[Point; 3]> = triangles
+        .iter()
+        .zip(&paired)
+        .filter(|(_, paired)| !**paired)
+        .map(|(triangle, _)| triangle.vertices)
+        .collect();
+
+    if options.all_quads {
+        let mut split = Vec::new();
+        for vertices in &quads {
+            split.extend(split_into_quads(vertices));
+        }
+        for vertices in &leftovers {
+            split.extend(split_into_quads(vertices));
+        }
+        quads = split;
+        leftovers.clear();
+    }
+
+    let cells: Vec<&[Point]> = quads
+        .iter()
+        .map(|vertices| &vertices[..])
+        .chain(leftovers.iter().map(|vertices| &vertices[..]))
+        .collect();
+    let adjacencies = connect(&cells);
+
+    let (quad_adjacencies, triangle_adjacencies) = adjacencies.split_at(quads.len());
+
+    RecombinedMesh {
+        quads: quads
+            .iter()
+            .zip(quad_adjacencies)
+            .map(|(vertices, adjacencies)| {
+                build_quad(
+                    *vertices,
+                    [
+                        adjacencies[0],
+                        adjacencies[1],
+                        adjacencies[2],
+                        adjacencies[3],
+                    ],
+                )
+            })
+            .collect(),
+        triangles: leftovers
+            .iter()
+            .zip(triangle_adjacencies)
+            .map(|(vertices, adjacencies)| {
+                build_triangle(*vertices, [adjacencies[0], adjacencies[1], adjacencies[2]])
+            })
+            .collect(),
+    }
+}
+
+/// Splits a convex polygon into one quad per vertex, joining the center to the middle of the edges.
+fn split_into_quads(vertices: &[Point]) -> Vec<[Point; 4]> {
+    let count = vertices.len();
+    let center = &vertices
+        .iter()
+        .fold(build_point(0.0, 0.0), |sum, vertex| &sum + vertex)
+        / count as f64;
+    let middle = |i: usize| &(&vertices[i] + &vertices[(i + 1) % count]) / 2.0;
+
+    (0..count)
+        .map(|i| {
+            [
+                vertices[i],
+                middle(i),
+                center,
+                middle((i + count - 1) % count),
+            ]
+        })
+        .collect()
+}
@@ -0,0 +1,259 @@
+//! Smoothing of triangulations, moving the interior vertices to improve the shape of the triangles.
+//!
+//! Boundary vertices are never moved, and a move is only done if every triangle around the vertex stays counter-clockwise.
+//! Each pass is followed by edge flips (see `delaunay_flips`), so that the triangulation is Delaunay again.
+
+use std::collections::HashMap;
+
+use crate::error::*;
+use crate::triangulation::*;
+use crate::types::*;
+use crate::validation::{boundary_vertices, point_key};
+
+/// Where each vertex is moved by a smoothing pass.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SmoothingMethod {
+    /// At the average of its neighbors.
+    Laplacian,
+    /// At the average of the positions bisecting the angles of its neighbors (Zhou and Shimada).
+    AngleBased,
+    /// At the position maximising the minimum angle of the triangles around it, found by a pattern search.
+    MinAngle,
+}
+
+/// Parameters of the smoothing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmoothingOptions {
+    pub method: SmoothingMethod,
+    /// Number of passes over every vertex.
+    pub iterations: usize,
+}
+
+impl Default for SmoothingOptions {
+    fn default() -> Self {
+        SmoothingOptions {
+            method: SmoothingMethod::Laplacian,
+            iterations: 5,
+        }
+    }
+}
+
+/// Triangles around a vertex, with the local index of the vertex in each of them.
+pub(crate) type VertexStar = Vec<(usize, usize)>;
+
+/// Smooths a triangulation by moving its interior vertices.
+///
+/// The vertices of the constrained edges are not moved, and these edges are kept by the flips.
+/// A vertex is moved towards its new position only as far as no triangle around it is inverted.
+/// With `SmoothingMethod::MinAngle`, a vertex is only moved if the smallest angle around it increases.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// // Grid with its interior points shifted
+/// let vertices: Vec<Point> = (0..8)
+///     .flat_map(|i| (0..8).map(move |j| (i, j)))
+///     .map(|(i, j)| {
+///         let shift = if i % 7 == 0 || j % 7 == 0 { 0.0 } else { 0.3 * ((i * 7 + j * 3) as f64).sin() };
+///         build_point(i as f64 + shift, j as f64 - shift)
+///     })
+///     .collect();
+/// let min_angle = |triangles: &[Triangle]| {
+///     triangles
+///         .iter()
+///         .flat_map(|triangle| triangle.angles())
+///         .fold(f64::MAX, f64::min)
+/// };
+///
+/// for method in [SmoothingMethod::Laplacian, SmoothingMethod::AngleBased, SmoothingMethod::MinAngle] {
+///     let mut triangles = triangulate(&vertices).unwrap();
+///     let before = min_angle(&triangles);
+///
+///     smooth(&mut triangles, &[], &SmoothingOptions { method, iterations: 5 }).unwrap();
+///
+///     assert!(min_angle(&triangles) > before);
+///     assert!(triangles.iter().all(|triangle| triangle.signed_area() > 0.0));
+///     assert!(validate(&unique_vertices(&triangles), &triangles, &[]).is_empty());
+/// }
+/// ```
+pub fn smooth(
+    triangles: &mut [Triangle],
+    constrained_edges: &[(Point, Point)],
+    options: &SmoothingOptions,
+) -> Result<(), MeshError> {
+    for _ in 0..options.iterations {
+        let (stars, is_boundary) = vertex_stars(triangles, constrained_edges);
+
+        for (star, is_boundary) in stars.iter().zip(is_boundary) {
+            if is_boundary {
+                continue;
+            }
+            let (id, local_id) = star[0];
+            let current = triangles[id].vertices[local_id];
+
+            let target = match options.method {
+                SmoothingMethod::Laplacian => laplacian_position(triangles, star),
+                SmoothingMethod::AngleBased => angle_based_position(triangles, star),
+                SmoothingMethod::MinAngle => min_angle_position(triangles, star),
+            };
+
+            // The move is shortened until no triangle is inverted
+            for relaxation in [1.0, 0.5, 0.25] {
+                let position = &(&current * (1.0 - relaxation)) + &(&target * relaxation);
+                if star_min_angle(triangles, star, &position).is_some() {
+                    for &(id, local_id) in star {
+                        triangles[id].vertices[local_id] = position;
+                    }
+                    break;
+                }
+            }
+        }
+
+        delaunay_flips(triangles, constrained_edges)?;
+    }
+
+    Ok(())
+}
+
+/// Finds the triangles around each vertex, and whether the vertex is on the boundary or on a constrained edge.
+pub(crate) fn vertex_stars(
+    triangles: &[Triangle],
+    constrained_edges: &[(Point, Point)],
+) -> (Vec<VertexStar>, Vec<bool>) {
+    let boundary = boundary_vertices(triangles);
+    let mut ids: HashMap<(u64, u64), usize> = HashMap::new();
+    let mut stars: Vec<VertexStar> = Vec::new();
+    let mut is_boundary: Vec<bool> = Vec::new();
+
+    for (id, triangle) in triangles.iter().enumerate() {
+        for local_id in 0..3 {
+            let key = point_key(&triangle.vertices[local_id]);
+            let vertex_id = *ids.entry(key).or_insert(stars.len());
+            if vertex_id == stars.len() {
+                stars.push(Vec::new());
+                is_boundary.push(boundary.contains(&key));
+            }
+            stars[vertex_id].push((id, local_id));
+        }
+    }
+
+    for (start, end) in constrained_edges {
+        for point in [start, end] {
+            if let Some(&vertex_id) = ids.get(&point_key(point)) {
+                is_boundary[vertex_id] = true;
+            }
+        }
+    }
+
+    (stars, is_boundary)
+}
+
+/// Gives the smallest angle of the triangles around a vertex moved to `position`,
+/// or `None` if one of them would be inverted.
+fn star_min_angle(triangles: &[Triangle], star: &VertexStar, position: &Point) -> Option<f64> {
+    let mut min_angle = f64::MAX;
+
+    for &(id, local_id) in star {
+        let mut triangle = triangles[id];
+        triangle.vertices[local_id] = *position;
+        if triangle.signed_area() <= 0.0 {
+            return None;
+        }
+        min_angle = triangle.angles().into_iter().fold(min_angle, f64::min);
+    }
+
+    Some(min_angle)
+}
+
+/// Gives the two other vertices of each triangle around a vertex, counter-clockwise.
+fn ring_edges(triangles: &[Triangle], star: &VertexStar) -> Vec<(Point, Point)> {
+    star.iter()
+        .map(|&(id, local_id)| {
+            let vertices = &triangles[id].vertices;
+            (vertices[(local_id + 1) % 3], vertices[(local_id + 2) % 3])
+        })
+        .collect()
+}
+
+fn laplacian_position(triangles: &[Triangle], star: &VertexStar) -> Point {
+    // Around an interior vertex, each neighbor starts exactly one edge of the ring
+    let ring = ring_edges(triangles, star);
+    &ring
+        .iter()
+        .fold(build_point(0.0, 0.0), |sum, (start, _)| &sum + start)
+        / ring.len() as f64
+}
+
+fn angle_based_position(triangles: &[Triangle], star: &VertexStar) -> Point {
+    let (id, local_id) = star[0];
+    let current = triangles[id].vertices[local_id];
+    let ring = ring_edges(triangles, star);
+
+    let positions: Vec<Point> = ring
+        .iter()
+        .filter_map(|(neighbor, next)| {
+            // Edge of the ring ending at this neighbor
+            let previous = ring.iter().find(|(_, end)| end == neighbor)?.0;
+
+            let (to_next, to_previous) =
+                (neighbor.segment_to(next), neighbor.segment_to(&previous));
+            let cross = to_next.x * to_previous.y - to_next.y * to_previous.x;
+            let mut angle = cross.atan2(&to_next * &to_previous);
+            if angle < 0.0 {
+                angle += 2.0 * std::f64::consts::PI;
+            }
+
+            // The vertex is rotated around the neighbor onto the bisector of its angle
+            let direction = to_next.normalize();
+            let (cos, sin) = ((angle / 2.0).cos(), (angle / 2.0).sin());
+            let distance = neighbor.segment_to(&current).norm();
+            Some(build_point(
+                neighbor.x + distance * (direction.x * cos - direction.y * sin),
+                neighbor.y + distance * (direction.x * sin + direction.y * cos),
+            ))
+        })
+        .collect();
+
+    if positions.is_empty() {
+        return current;
+    }
+    &positions
+        .iter()
+        .fold(build_point(0.0, 0.0), |sum, position| &sum + position)
+        / positions.len() as f64
+}
+
+fn min_angle_position(triangles: &[Triangle], star: &VertexStar) -> Point {
+    let (id, local_id) = star[0];
+    let mut best = triangles[id].vertices[local_id];
+    let mut best_angle = star_min_angle(triangles, star, &best).unwrap_or(f64::MIN);
+
+    let ring = ring_edges(triangles, star);
+    let mut step = 0.1
+        * ring
+            .iter()
+            .map(|(neighbor, _)| best.segment_to(neighbor).norm())
+            .sum::<f64>()
+        / ring.len() as f64;
+
+    for _ in 0..20 {
+        let candidates = [(step, 0.0), (-step, 0.0), (0.0, step), (0.0, -step)];
+        let improvement = candidates
+            .iter()
+            .map(|(dx, dy)| build_point(best.x + dx, best.y + dy))
+            .filter_map(|candidate| {
+                star_min_angle(triangles, star, &candidate).map(|angle| (candidate, angle))
+            })
+            .filter(|(_, angle)| *angle > best_angle)
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        match improvement {
+            Some((candidate, angle)) => (best, best_angle) = (candidate, angle),
+            None => step /= 2.0,
+        }
+    }
+
+    best
+}
@@ -0,0 +1,472 @@
+//! Structured meshes of blocks mapped on four boundary curves.
+//!
+//! The nodes of a block are placed by transfinite interpolation (Coons patch) of its boundary curves,
+//! so the structure of the grid gives the adjacencies and the boundaries directly.
+
+use std::collections::HashMap;
+
+use crate::error::*;
+use crate::geometry::*;
+use crate::mixed_mesh::*;
+use crate::types::*;
+
+/// Distribution of the nodes along a direction of a block.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Stretching {
+    #[default]
+    Uniform,
+    /// Each cell is `ratio` times longer than the previous one.
+    Geometric(f64),
+    /// Cells are clustered at both ends, more strongly as the factor grows.
+    Tanh(f64),
+}
+
+impl Stretching {
+    /// Gives the parameters of the `cells + 1` nodes, from 0 to 1.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use meshing::*;
+    ///
+    /// let parameters = Stretching::Geometric(2.0).distribution(3);
+    ///
+    /// assert_eq!(parameters.len(), 4);
+    /// assert!((parameters[1] - 1.0 / 7.0).abs() < 1e-12);
+    /// assert!((parameters[2] - 3.0 / 7.0).abs() < 1e-12);
+    /// ```
+    pub fn distribution(&self, cells: usize) -> Vec<f64> {
+        (0..=cells)
+            .map(|i| {
+                let xi = i as f64 / cells as f64;
+                match *self {
+                    Stretching::Geometric(ratio) if ratio != 1.0 => {
+                        (ratio.powi(i as i32) - 1.0) / (ratio.powi(cells as i32) - 1.0)
+                    }
+                    Stretching::Tanh(factor) if factor != 0.0 => {
+                        0.5 * (1.0 + (factor * (xi - 0.5)).tanh() / (factor / 2.0).tanh())
+                    }
+                    _ => xi,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Shape of the cells of a structured mesh.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CellShape {
+    #[default]
+    Quad,
+    /// Each quad of the grid is split in two along its diagonal from the south-west corner.
+    Triangle,
+}
+
+/// Four sided block of a structured mesh.
+///
+/// The south and north curves go from west to east, and the west and east curves from south to north,
+/// so that the block is counter-clockwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+    pub south: Curve,
+    pub east: Curve,
+    pub north: Curve,
+    pub west: Curve,
+    /// Number of cells in the west-east and south-north directions.
+    pub cells: (usize, usize),
+    pub stretching: (Stretching, Stretching),
+    /// Tags of the south, east, north and west sides.
+    pub tags: [String; 4],
+}
+
+impl Block {
+    /// Places the nodes of the grid, `nodes[i][j]` being the i-th from the west and j-th from the south.
+    /// The nodes on the sides are exactly on the curves.
+    pub fn nodes(&self) -> Vec<Vec<Point>> {
+        let (u, v) = (
+            self.stretching.0.distribution(self.cells.0),
+            self.stretching.1.distribution(self.cells.1),
+        );
+        let (south_west, south_east) = (self.south.point_at(0.0), self.south.point_at(1.0));
+        let (north_west, north_east) = (self.north.point_at(0.0), self.north.point_at(1.0));
+
+        (0..=self.cells.0)
+            .map(|i| {
+                (0..=self.cells.1)
+                    .map(|j| {
+                        if j == 0 {
+                            return self.south.point_at(u[i]);
+                        } else if j == self.cells.1 {
+                            return self.north.point_at(u[i]);
+                        } else if i == 0 {
+                            return self.west.point_at(v[j]);
+                        } else if i == self.cells.0 {
+                            return self.east.point_at(v[j]);
+                        }
+
+                        let (u, v) = (u[i], v[j]);
+                        let sides = &(&(&self.south.point_at(u) * (1.0 - v))
+                            + &(&self.north.point_at(u) * v))
+                            + &(&(&self.west.point_at(v) * (1.0 - u))
+                                + &(&self.east.point_at(v) * u));
+                        let corners = &(&(&south_west * ((1.0 - u) * (1.0 - v)))
+                            + &(&south_east * (u * (1.0 - v))))
+                            + &(&(&north_west * ((1.0 - u) * v)) + &(&north_east * (u * v)));
+
+                        &sides - &corners
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Creates a block with a uniform distribution and its sides tagged `south`, `east`, `north` and `west`.
+pub fn build_block(
+    south: Curve,
+    east: Curve,
+    north: Curve,
+    west: Curve,
+    cells: (usize, usize),
+) -> Block {
+    Block {
+        south,
+        east,
+        north,
+        west,
+        cells,
+        stretching: (Stretching::Uniform, Stretching::Uniform),
+        tags: [
+            "south".to_string(),
+            "east".to_string(),
+            "north".to_string(),
+            "west".to_string(),
+        ],
+    }
+}
+
+/// Creates a rectangular block from its south-west and north-east corners.
+pub fn build_rectangle_block(min: Point, max: Point, cells: (usize, usize)) -> Block {
+    let (south_east, north_west) = (build_point(max.x, min.y), build_point(min.x, max.y));
+
+    build_block(
+        Curve::Line(min, south_east),
+        Curve::Line(south_east, max),
+        Curve::Line(north_west, max),
+        Curve::Line(min, north_west),
+        cells,
+    )
+}
+
+/// Creates a block between two concentric arcs, the inner one being the south side.
+/// Angles are in radians, the block covering the angles from `start` to `end`, with its west side at `end`
+/// and its east side at `start`.
+pub fn build_annulus_block(
+    center: Point,
+    radii: (f64, f64),
+    start: f64,
+    end: f64,
+    cells: (usize, usize),
+) -> Block {
+    let point = |radius: f64, angle: f64| {
+        build_point(
+            center.x + radius * angle.cos(),
+            center.y + radius * angle.sin(),
+        )
+    };
+    // Going clockwise along the arcs keeps the block counter-clockwise
+    let arc = |radius: f64| Curve::Arc {
+        center,
+        radius,
+        start: end,
+        end: start,
+    };
+
+    build_block(
+        arc(radii.0),
+        Curve::Line(point(radii.0, start), point(radii.1, start)),
+        arc(radii.1),
+        Curve::Line(point(radii.0, end), point(radii.1, end)),
+        cells,
+    )
+}
+
+/// Edge of a cell on the boundary of the mesh, with the tag of the boundary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundaryFace {
+    pub cell: usize,
+    /// Local index of the edge in the cell.
+    pub edge: usize,
+    pub tag: String,
+}
+
+/// Mesh with its boundary edges tagged.
+#[derive(Debug, Clone, Default)]
+pub struct StructuredMesh {
+    pub mesh: MixedMesh,
+    pub boundaries: Vec<BoundaryFace>,
+}
+
+/// Meshes a block with quads or triangles.
+///
+/// Cells are numbered from the south-west, west to east then south to north, with two triangles per quad of the grid.
+/// The boundary faces are listed side by side, south, east, north then west.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let mut block = build_rectangle_block(build_point(0.0, 0.0), build_point(2.0, 1.0), (4, 2));
+/// block.stretching.1 = Stretching::Geometric(1.5);
+///
+/// let quads = mesh_block(&block, CellShape::Quad).unwrap();
+/// assert_eq!(quads.mesh.len(), 8);
+/// assert_eq!(quads.boundaries.len(), 12);
+/// assert_eq!(quads.mesh.cells[0].iter_adjacencies().nth(1), Some(&Some(Neighbor::Cell(1))));
+///
+/// let triangles = mesh_block(&block, CellShape::Triangle).unwrap();
+/// let area: f64 = triangles.mesh.cells.iter().map(|cell| cell.signed_area()).sum();
+/// assert_eq!(triangles.mesh.len(), 16);
+/// assert!((area - 2.0).abs() < 1e-12);
+/// assert_eq!(
+///     triangles.boundaries.iter().filter(|face| face.tag == "west").count(),
+///     2
+/// );
+/// ```
+pub fn mesh_block(block: &Block, shape: CellShape) -> Result<StructuredMesh, MeshError> {
+    mesh_nodes(block, &block.nodes(), shape)
+}
+
+/// Meshes several blocks into one conforming mesh.
+///
+/// Nodes on the sides of the blocks are merged when they coincide, up to rounding errors,
+/// so that the adjacencies cross the interfaces between blocks.
+/// Two blocks are only conforming if they have the same nodes on their shared side,
+/// otherwise their sides stay boundaries.
+///
+/// Cells are numbered block by block, and only the sides of the blocks which are not interfaces are in the boundary faces.
+/// Fails with `MeshError::DegenerateInput` if all the nodes coincide.
+///
+/// # Example
+///
+/// O-grid around a cylinder, made of four blocks between the cylinder and a square:
+///
+/// ```rust
+/// use meshing::*;
+/// use std::f64::consts::PI;
+///
+/// let center = build_point(1.0, 0.5);
+/// let on_circle = |radius: f64, angle: f64| {
+///     build_point(center.x + radius * angle.cos(), center.y + radius * angle.sin())
+/// };
+///
+/// let blocks: Vec<Block> = (0..4)
+///     .map(|k| {
+///         let (start, end) = (PI / 4.0 + k as f64 * PI / 2.0, 3.0 * PI / 4.0 + k as f64 * PI / 2.0);
+///         let mut block = build_block(
+///             Curve::Arc { center, radius: 0.25, start: end, end: start },
+///             Curve::Line(on_circle(0.25, start), on_circle(0.5 * 2f64.sqrt(), start)),
+///             Curve::Line(on_circle(0.5 * 2f64.sqrt(), end), on_circle(0.5 * 2f64.sqrt(), start)),
+///             Curve::Line(on_circle(0.25, end), on_circle(0.5 * 2f64.sqrt(), end)),
+///             (8, 6),
+///         );
+///         block.stretching.1 = Stretching::Geometric(1.2);
+///         block.tags[0] = "cylinder".to_string();
+///         block.tags[2] = "farfield".to_string();
+///         block
+///     })
+///     .collect();
+///
+/// let o_grid = mesh_blocks(&blocks, CellShape::Quad).unwrap();
+///
+/// assert_eq!(o_grid.mesh.len(), 4 * 8 * 6);
+/// assert_eq!(o_grid.boundaries.len(), 2 * 4 * 8);
+/// assert!(o_grid.boundaries.iter().all(|face| face.tag == "cylinder" || face.tag == "farfield"));
+///
+/// let point = Curve::Line(center, center);
+/// let collapsed = build_block(point.clone(), point.clone(), point.clone(), point, (2, 2));
+/// assert_eq!(mesh_blocks(&[collapsed], CellShape::Quad).unwrap_err(), MeshError::DegenerateInput);
+/// ```
+pub fn mesh_blocks(blocks: &[Block], shape: CellShape) -> Result<StructuredMesh, MeshError> {
+    if blocks.is_empty() {
+        return Err(MeshError::EmptyInput);
+    }
+
+    let mut grids: Vec<Vec<Vec<Point>>> = blocks.iter().map(|block| block.nodes()).collect();
+
+    let (mut min, mut max) = (grids[0][0][0], grids[0][0][0]);
+    for node in grids.iter().flatten().flatten() {
+        min = build_point(min.x.min(node.x), min.y.min(node.y));
+        max = build_point(max.x.max(node.x), max.y.max(node.y));
+    }
+    let tolerance = 1e-9 * min.segment_to(&max).norm();
+    if tolerance <= 0.0 {
+        return Err(MeshError::DegenerateInput);
+    }
+
+    // Nodes on the sides of a block are replaced by the coinciding nodes of the previous blocks,
+    // found in the buckets of the tolerance size around them
+    let bucket = |point: &Point| {
+        (
+            ((point.x - min.x) / tolerance).floor() as i64,
+            ((point.y - min.y) / tolerance).floor() as i64,
+        )
+    };
+    let mut side_nodes: HashMap<(i64, i64), Vec<Point>> = HashMap::new();
+    for grid in &mut grids {
+        let (last_i, last_j) = (grid.len() - 1, grid[0].len() - 1);
+        let mut block_side_nodes = Vec::new();
+
+        for (i, column) in grid.iter_mut().enumerate() {
+            for (j, node) in column.iter_mut().enumerate() {
+                if i != 0 && i != last_i && j != 0 && j != last_j {
+                    continue;
+                }
+                let (i, j) = bucket(node);
+                if let Some(existing) = (i - 1..=i + 1)
+                    .flat_map(|i| (j - 1..=j + 1).map(move |j| (i, j)))
+                    .filter_map(|key| side_nodes.get(&key))
+                    .flatten()
+                    .find(|existing| existing.segment_to(node).norm() <= tolerance)
+                {
+                    *node = *existing;
+                }
+                block_side_nodes.push(*node);
+            }
+        }
+
+        for node in block_side_nodes {
+            side_nodes.entry(bucket(&node)).or_default().push(node);
+        }
+    }
+
+    let mut cells = Vec::new();
+    let mut boundaries = Vec::new();
+    for (block, grid) in blocks.iter().zip(&grids) {
+        let offset = cells.len();
+        let part = mesh_nodes(block, grid, shape).map_err(|error| match error {
+            MeshError::InvertedCell { cell } => MeshError::InvertedCell {
+                cell: cell + offset,
+            },
+            error => error,
+        })?;
+
+        cells.extend(part.mesh.cells);
+        boundaries.extend(part.boundaries.into_iter().map(|face| BoundaryFace {
+            cell: face.cell + offset,
+            ..face
+        }));
+    }
+
+    let mesh = MixedMesh::from_cells(cells);
+    boundaries.retain(|face| {
+        mesh.cells[face.cell].iter_adjacencies().nth(face.edge) == Some(&Some(Neighbor::Boundary))
+    });
+
+    Ok(StructuredMesh { mesh, boundaries })
+}
+
+/// Builds the cells of a block from its nodes, given as by `Block::nodes`.
+fn mesh_nodes(
+    block: &Block,
+    nodes: &[Vec<Point>],
+    shape: CellShape,
+) -> Result<StructuredMesh, MeshError> {
+    let (cells_u, cells_v) = block.cells;
+    if cells_u == 0 || cells_v == 0 {
+        return Err(MeshError::DegenerateInput);
+    }
+
+    let quad_id = |i: usize, j: usize| j * cells_u + i;
+    // Cell and local edge on each side of the quad (i, j), in the order south, east, north, west
+    let side = |i: usize, j: usize, side: usize| match shape {
+        CellShape::Quad => (quad_id(i, j), side),
+        CellShape::Triangle => match side {
+            0 => (2 * quad_id(i, j), 0),
+            1 => (2 * quad_id(i, j), 1),
+            2 => (2 * quad_id(i, j) + 1, 1),
+            _ => (2 * quad_id(i, j) + 1, 2),
+        },
+    };
+    // Neighbor across a side of the quad (i, j), with the tag of the side if it is a boundary
+    let across = |i: usize, j: usize, direction: usize| {
+        let neighbor = match direction {
+            0 if j > 0 => Some((i, j - 1)),
+            1 if i + 1 < cells_u => Some((i + 1, j)),
+            2 if j + 1 < cells_v => Some((i, j + 1)),
+            3 if i > 0 => Some((i - 1, j)),
+            _ => None,
+        };
+        match neighbor {
+            Some((i, j)) => Some(Neighbor::Cell(side(i, j, (direction + 2) % 4).0)),
+            None => Some(Neighbor::Boundary),
+        }
+    };
+
+    let mut cells: Vec<MeshCell> = Vec::new();
+    for j in 0..cells_v {
+        for i in 0..cells_u {
+            let corners = [
+                nodes[i][j],
+                nodes[i + 1][j],
+                nodes[i + 1][j + 1],
+                nodes[i][j + 1],
+            ];
+            let adjacencies = [
+                across(i, j, 0),
+                across(i, j, 1),
+                across(i, j, 2),
+                across(i, j, 3),
+            ];
+
+            match shape {
+                CellShape::Quad => cells.push(build_quad(corners, adjacencies).into()),
+                CellShape::Triangle => {
+                    let id = 2 * quad_id(i, j);
+                    cells.push(
+                        build_triangle(
+                            [corners[0], corners[1], corners[2]],
+                            [adjacencies[0], adjacencies[1], Some(Neighbor::Cell(id + 1))],
+                        )
+                        .into(),
+                    );
+                    cells.push(
+                        build_triangle(
+                            [corners[0], corners[2], corners[3]],
+                            [Some(Neighbor::Cell(id)), adjacencies[2], adjacencies[3]],
+                        )
+                        .into(),
+                    );
+                }
+            }
+        }
+    }
+
+    if let Some(cell) = cells.iter().position(|cell| cell.signed_area() <= 0.0) {
+        return Err(MeshError::InvertedCell { cell });
+    }
+
+    let mut boundaries = Vec::new();
+    let sides: [Vec<(usize, usize)>; 4] = [
+        (0..cells_u).map(|i| (i, 0)).collect(),
+        (0..cells_v).map(|j| (cells_u - 1, j)).collect(),
+        (0..cells_u).map(|i| (i, cells_v - 1)).collect(),
+        (0..cells_v).map(|j| (0, j)).collect(),
+    ];
+    for (direction, quads) in sides.iter().enumerate() {
+        for &(i, j) in quads {
+            let (cell, edge) = side(i, j, direction);
+            boundaries.push(BoundaryFace {
+                cell,
+                edge,
+                tag: block.tags[direction].clone(),
+            });
+        }
+    }
+
+    Ok(StructuredMesh {
+        mesh: MixedMesh { cells },
+        boundaries,
+    })
+}
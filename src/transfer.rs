@@ -0,0 +1,242 @@
+//! Transfer of the fields of a solver from a mesh to another, e.g. from the mesh before an adaptation to the new one.
+//!
+//! The points of the target mesh are located in the source mesh by walking from the cell found for the previous point,
+//! which is fast as consecutive vertices and cells of a mesh are usually close.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::cell_tree::*;
+use crate::error::*;
+use crate::incremental::walk_to_cell;
+use crate::triangulation::*;
+use crate::types::*;
+use crate::validation::point_key;
+
+/// Barycentric coordinate under which a point is outside of a triangle, so that points on the boundary of the source
+/// mesh are still found despite rounding errors.
+const LOCATION_TOLERANCE: f64 = 1e-10;
+
+/// Interpolates linearly a field given at the vertices of the source mesh to the vertices of the target mesh.
+///
+/// The values are given in the order of `unique_vertices` for both meshes.
+/// Fails with `MeshError::PointOutsideDomain` if a target vertex is outside of the source mesh.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let grid = |count: usize| -> Vec<Point> {
+///     (0..=count)
+///         .flat_map(|i| (0..=count).map(move |j| build_point(i as f64 / count as f64, j as f64 / count as f64)))
+///         .collect()
+/// };
+/// let source = triangulate(&grid(4)).unwrap();
+/// let target = triangulate(&grid(7)).unwrap();
+/// let field = |point: &Point| 2.0 * point.x - 3.0 * point.y;
+///
+/// let values: Vec<f64> = unique_vertices(&source).iter().map(field).collect();
+/// let interpolated = interpolate_vertex_field(&source, &values, &target).unwrap();
+///
+/// // A linear field is interpolated exactly
+/// for (vertex, value) in unique_vertices(&target).iter().zip(interpolated) {
+///     assert!((value - field(vertex)).abs() < 1e-12);
+/// }
+/// ```
+pub fn interpolate_vertex_field(
+    source: &[Triangle],
+    values: &[f64],
+    target: &[Triangle],
+) -> Result<Vec<f64>, MeshError> {
+    let source_vertices = unique_vertices(source);
+    if values.len() != source_vertices.len() {
+        return Err(MeshError::FieldSizeMismatch {
+            expected: source_vertices.len(),
+            found: values.len(),
+        });
+    }
+    let ids: HashMap<(u64, u64), usize> = source_vertices
+        .iter()
+        .enumerate()
+        .map(|(id, vertex)| (point_key(vertex), id))
+        .collect();
+
+    let mut hint = 0;
+    unique_vertices(target)
+        .iter()
+        .map(|vertex| {
+            hint = locate_from(vertex, source, hint)
+                .ok_or(MeshError::PointOutsideDomain { point: *vertex })?;
+
+            let triangle = &source[hint];
+            let (s, t) = triangle.barycentric_coordinates_from(vertex);
+            let [first, second, third] = triangle
+                .vertices
+                .map(|vertex| values[ids[&point_key(&vertex)]]);
+            Ok(s * first + t * second + (1.0 - s - t) * third)
+        })
+        .collect()
+}
+
+/// Transfers a field given as an average on each cell of the source mesh to the cells of the target mesh.
+///
+/// The value of a target cell is the average of the source values weighted by the area of their overlap with it,
+/// so that the integral of the field is kept when both meshes cover the same domain.
+/// The average is divided by the overlapped area and not by the area of the target cell, so the integral is not kept
+/// where target cells stick out of the source mesh: the values there are spread over the whole target cell.
+/// The source cells overlapping a target cell are found from the one containing its center, through the adjacencies,
+/// or from one overlapping it found with a `CellTree` if its center is outside of the source mesh.
+/// Fails with `MeshError::PointOutsideDomain` if a target cell does not overlap the source mesh.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let grid = |count: usize| -> Vec<Point> {
+///     (0..=count)
+///         .flat_map(|i| (0..=count).map(move |j| build_point(i as f64 / count as f64, j as f64 / count as f64)))
+///         .collect()
+/// };
+/// let source = triangulate(&grid(4)).unwrap();
+/// let target = triangulate(&grid(7)).unwrap();
+///
+/// let values: Vec<f64> = source.iter().map(|triangle| triangle.center().x.powi(2)).collect();
+/// let transferred = transfer_cell_field(&source, &values, &target).unwrap();
+///
+/// let integral = |triangles: &[Triangle], values: &[f64]| -> f64 {
+///     triangles.iter().zip(values).map(|(triangle, value)| triangle.signed_area() * value).sum()
+/// };
+/// assert!((integral(&source, &values) - integral(&target, &transferred)).abs() < 1e-12);
+///
+/// // Target cells sticking out of the source mesh get the average on their overlap with it
+/// let larger: Vec<Point> = grid(7).iter().map(|point| build_point(1.2 * point.x - 0.1, point.y)).collect();
+/// let target = triangulate(&larger).unwrap();
+/// let transferred = transfer_cell_field(&source, &vec![3.0; source.len()], &target).unwrap();
+/// assert!(transferred.iter().all(|value| (value - 3.0).abs() < 1e-12));
+/// assert!(integral(&target, &transferred) > 3.0);
+/// ```
+pub fn transfer_cell_field(
+    source: &[Triangle],
+    values: &[f64],
+    target: &[Triangle],
+) -> Result<Vec<f64>, MeshError> {
+    if values.len() != source.len() {
+        return Err(MeshError::FieldSizeMismatch {
+            expected: source.len(),
+            found: values.len(),
+        });
+    }
+
+    let tree = build_cell_tree(source);
+    let mut hint = 0;
+    target
+        .iter()
+        .map(|triangle| {
+            let center = triangle.center();
+            let start = match walk_to_cell(&center, source, hint) {
+                Some(id) => id,
+                // The walk stops on the boundary, and the center can be outside of the source mesh
+                // if the boundaries differ
+                None => {
+                    let (min, max) = bounding_box(&triangle.vertices)?;
+                    tree.cells_in_box(&min, &max)
+                        .into_iter()
+                        .find(|id| intersection_area(triangle, &source[*id]) > 0.0)
+                        .ok_or(MeshError::PointOutsideDomain { point: center })?
+                }
+            };
+            hint = start;
+
+            let (mut weighted_sum, mut total_area) = (0.0, 0.0);
+            let mut seen = HashSet::from([start]);
+            let mut stack = vec![start];
+            while let Some(id) = stack.pop() {
+                let area = intersection_area(triangle, &source[id]);
+                if area <= 0.0 {
+                    continue;
+                }
+                weighted_sum += area * values[id];
+                total_area += area;
+
+                for neighbor in source[id].adjacencies {
+                    if let Some(Neighbor::Cell(neighbor)) = neighbor {
+                        if seen.insert(neighbor) {
+                            stack.push(neighbor);
+                        }
+                    }
+                }
+            }
+
+            if total_area <= 0.0 {
+                return Err(MeshError::PointOutsideDomain { point: center });
+            }
+            Ok(weighted_sum / total_area)
+        })
+        .collect()
+}
+
+/// Computes the area of the intersection of two counter-clockwise triangles,
+/// by clipping the first one by each edge of the second one (Sutherland-Hodgman algorithm).
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let first = build_triangle(
+///     [build_point(0.0, 0.0), build_point(2.0, 0.0), build_point(0.0, 2.0)],
+///     [None; 3],
+/// );
+/// let second = build_triangle(
+///     [build_point(0.0, 0.0), build_point(1.0, 0.0), build_point(1.0, 1.0)],
+///     [None; 3],
+/// );
+///
+/// assert_eq!(intersection_area(&first, &second), 0.5);
+/// assert_eq!(intersection_area(&first, &first), 2.0);
+/// ```
+pub fn intersection_area(first: &Triangle, second: &Triangle) -> f64 {
+    let mut polygon = first.vertices.to_vec();
+
+    for edge in 0..3 {
+        let (start, end) = (&second.vertices[edge], &second.vertices[(edge + 1) % 3]);
+        let side = |point: &Point| orientation(start, end, point);
+
+        let mut clipped = Vec::with_capacity(polygon.len() + 1);
+        for (i, current) in polygon.iter().enumerate() {
+            let next = &polygon[(i + 1) % polygon.len()];
+            let (current_side, next_side) = (side(current), side(next));
+
+            if current_side >= 0.0 {
+                clipped.push(*current);
+            }
+            // The polygon edge crosses the line of the clipping edge
+            if (current_side >= 0.0) != (next_side >= 0.0) {
+                let ratio = current_side / (current_side - next_side);
+                let direction = current.segment_to(next);
+                clipped.push(build_point(
+                    current.x + ratio * direction.x,
+                    current.y + ratio * direction.y,
+                ));
+            }
+        }
+
+        if clipped.len() < 3 {
+            return 0.0;
+        }
+        polygon = clipped;
+    }
+
+    // Fan from the first vertex, which is more precise than the shoelace formula far from the origin
+    (1..polygon.len() - 1)
+        .map(|i| orientation(&polygon[0], &polygon[i], &polygon[i + 1]) / 2.0)
+        .sum()
+}
+
+/// Finds the triangle containing a point by walking from the hint, or by going through every triangle
+/// if the walk stops on the boundary.
+fn locate_from(point: &Point, triangles: &[Triangle], hint: usize) -> Option<usize> {
+    walk_to_cell(point, triangles, hint)
+        .or_else(|| locate_for_insertion(point, triangles, LOCATION_TOLERANCE).ok())
+}
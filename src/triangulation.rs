@@ -1,16 +1,511 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::error::*;
+use crate::incremental::walk_to_cell;
 use crate::types::*;
+use crate::validation::{edge_key, point_key, DELAUNAY_TOLERANCE};
 
-use core::panic;
+/// Coordinates of the big triangle containing every rescaled vertex at the start of the triangulation.
+const BIG_TRIANGLE: [(f64, f64); 3] = [(-100.0, -100.0), (100.0, 0.0), (0.0, 100.0)];
 
-/// Rescale all vertices to make everything fit in [0, 1].
-/// Ensures for exemple the the big triangle will always be big enough.
+/// What to do with a point closer than the tolerance to a point already in the mesh.
 ///
-/// Returns the original length of the point cloud to be able to scale it back
-pub fn rescale_vertices(vertices: &mut Vec<Point>) -> (Point, Point) {
-    let (mut x_max, mut x_min) = (vertices[0].x, vertices[0].x);
-    let (mut y_max, mut y_min) = (vertices[0].y, vertices[0].y);
+/// `Merge` and `Skip` give the same mesh, the first point being kept as it is, and only differ in how the point
+/// is reported: `merged` gives the vertex it is replaced by, while `skipped` only lists it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DuplicatePolicy {
+    /// The point is considered to be the same vertex as the first one, and is reported in `merged`.
+    Merge,
+    /// The point is ignored, and is reported in `skipped`.
+    Skip,
+    /// The triangulation fails with `MeshError::DuplicatePoint`.
+    Error,
+}
+
+/// How each vertex is inserted in the triangulation.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum InsertionKernel {
+    /// The triangle containing the point is split, then edges are flipped until the mesh is Delaunay (see `insert_vertex`).
+    #[default]
+    Flips,
+    /// The triangles whose circumcircle contains the point are removed, and the cavity is joined to the point
+    /// (see `insert_vertex_bowyer_watson`).
+    BowyerWatson,
+}
+
+/// Parameters of the triangulation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TriangulationOptions {
+    /// Distance under which two points are duplicates, or a point is on an edge.
+    /// Relative to the largest side of the bounding box of the point cloud.
+    pub tolerance: f64,
+    pub duplicate_policy: DuplicatePolicy,
+    pub kernel: InsertionKernel,
+}
+
+impl Default for TriangulationOptions {
+    fn default() -> Self {
+        TriangulationOptions {
+            tolerance: 1e-10,
+            duplicate_policy: DuplicatePolicy::Merge,
+            kernel: InsertionKernel::Flips,
+        }
+    }
+}
+
+/// Result of `triangulate_with`.
+#[derive(Debug, Clone)]
+pub struct TriangulationOutput {
+    pub triangles: Vec<Triangle>,
+    /// Input indices merged with another vertex, as (merged index, kept index).
+    pub merged: Vec<(usize, usize)>,
+    /// Input indices ignored because they were duplicates.
+    pub skipped: Vec<usize>,
+}
+
+/// Triangulates a point cloud with an unconstrained Delaunay triangulation, using the default options.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let vertices = vec![
+///     build_point(0.0, 0.0),
+///     build_point(1.0, 0.0),
+///     build_point(1.0, 1.0),
+///     build_point(0.0, 1.0),
+/// ];
+///
+/// assert_eq!(triangulate(&vertices).unwrap().len(), 2);
+/// assert_eq!(triangulate(&[]).unwrap_err(), MeshError::EmptyInput);
+/// ```
+pub fn triangulate(vertices: &[Point]) -> Result<Vec<Triangle>, MeshError> {
+    triangulate_with(vertices, &TriangulationOptions::default()).map(|output| output.triangles)
+}
+
+/// Triangulates a point cloud with an unconstrained Delaunay triangulation.
+///
+/// Goes through the whole pipeline: rescaling, removal of duplicates, insertion of every vertex in a big triangle,
+/// removal of the big triangle and scaling back. The triangles use the exact coordinates of the input points.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let vertices = vec![
+///     build_point(0.0, 0.0),
+///     build_point(2.0, 0.0),
+///     build_point(1.0, 0.0),
+///     build_point(1.0, 1.0),
+///     build_point(1.0, 1e-12),
+/// ];
+///
+/// let output = triangulate_with(&vertices, &TriangulationOptions::default()).unwrap();
+///
+/// // The point in the middle of the bottom edge splits it in two
+/// assert_eq!(output.triangles.len(), 2);
+/// assert_eq!(output.merged, vec![(4, 2)]);
+/// ```
+pub fn triangulate_with(
+    vertices: &[Point],
+    options: &TriangulationOptions,
+) -> Result<TriangulationOutput, MeshError> {
+    let mut rescaled = vertices.to_vec();
+    rescale_vertices(&mut rescaled)?;
+
+    let mut merged = Vec::new();
+    let mut skipped = Vec::new();
+    let duplicates = find_duplicates(&rescaled, options.tolerance);
+
+    for (i, duplicate) in duplicates.iter().enumerate() {
+        match (duplicate, options.duplicate_policy) {
+            (None, _) => (),
+            (Some(kept), DuplicatePolicy::Merge) => merged.push((i, *kept)),
+            (Some(_), DuplicatePolicy::Skip) => skipped.push(i),
+            (Some(_), DuplicatePolicy::Error) => {
+                return Err(MeshError::DuplicatePoint { point: vertices[i] })
+            }
+        }
+    }
+
+    let kept: Vec<usize> = (0..vertices.len())
+        .filter(|i| duplicates[*i].is_none())
+        .collect();
+    let rescaled: Vec<Point> = kept.iter().map(|i| rescaled[*i]).collect();
+
+    if are_collinear(&rescaled) {
+        return Err(MeshError::DegenerateInput);
+    }
+
+    let big_triangle = build_triangle(
+        [
+            build_point(BIG_TRIANGLE[0].0, BIG_TRIANGLE[0].1),
+            build_point(BIG_TRIANGLE[1].0, BIG_TRIANGLE[1].1),
+            build_point(BIG_TRIANGLE[2].0, BIG_TRIANGLE[2].1),
+        ],
+        [None; 3],
+    );
+
+    let mut mesh = vec![big_triangle];
+
+    // Each point is found by walking from the last triangle created, which touches the previous point
+    for point in &rescaled {
+        let current_triangle = match walk_to_cell(point, &mesh, mesh.len() - 1) {
+            Some(id) => id,
+            None => locate_for_insertion(point, &mesh, options.tolerance)?,
+        };
+        match options.kernel {
+            InsertionKernel::Flips => {
+                insert_vertex_in(point, &mut mesh, options.tolerance, current_triangle)?
+            }
+            InsertionKernel::BowyerWatson => insert_vertex_bowyer_watson_in(
+                point,
+                &mut mesh,
+                options.tolerance,
+                current_triangle,
+            )?,
+        }
+    }
+
+    remove_big_triangle(&mut mesh, &big_triangle);
+
+    // Scaling back could round the coordinates, so the input points are used instead
+    let original: HashMap<(u64, u64), Point> = kept
+        .iter()
+        .zip(&rescaled)
+        .map(|(i, point)| (point_key(point), vertices[*i]))
+        .collect();
+    for triangle in mesh.iter_mut() {
+        for vertex in triangle.vertices.iter_mut() {
+            *vertex = original[&point_key(vertex)];
+        }
+    }
+
+    Ok(TriangulationOutput {
+        triangles: mesh,
+        merged,
+        skipped,
+    })
+}
+
+/// Finds for each vertex the index of a previous vertex closer than the tolerance, if any.
+/// The points are bucketed on a grid of the size of the tolerance, so only the neighboring buckets are checked.
+fn find_duplicates(vertices: &[Point], tolerance: f64) -> Vec<Option<usize>> {
+    let bucket_size = tolerance.max(f64::EPSILON);
+    let bucket = |point: &Point| {
+        (
+            (point.x / bucket_size).floor() as i64,
+            (point.y / bucket_size).floor() as i64,
+        )
+    };
+
+    let mut buckets: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+    let mut duplicates = Vec::with_capacity(vertices.len());
+
+    for (i, vertex) in vertices.iter().enumerate() {
+        let (x, y) = bucket(vertex);
+
+        let duplicate = (x - 1..=x + 1)
+            .flat_map(|bx| (y - 1..=y + 1).map(move |by| (bx, by)))
+            .filter_map(|key| buckets.get(&key))
+            .flatten()
+            .find(|&&kept| vertex.segment_to(&vertices[kept]).norm() <= tolerance)
+            .copied();
+
+        if duplicate.is_none() {
+            buckets.entry((x, y)).or_default().push(i);
+        }
+        duplicates.push(duplicate);
+    }
+
+    duplicates
+}
+
+/// Inserts a vertex in a Delaunay triangulation, keeping it Delaunay.
+///
+/// A point closer than the tolerance to an edge is inserted on the edge, splitting the two adjacent triangles.
+/// A point closer than the tolerance to a vertex is a duplicate.
+pub fn insert_vertex(
+    point: &Point,
+    triangles: &mut Vec<Triangle>,
+    tolerance: f64,
+) -> Result<(), MeshError> {
+    let current_triangle = locate_for_insertion(point, triangles, tolerance)?;
+    insert_vertex_in(point, triangles, tolerance, current_triangle)
+}
+
+/// Inserts a vertex with `insert_vertex`, in the triangle already found to contain it.
+pub(crate) fn insert_vertex_in(
+    point: &Point,
+    triangles: &mut Vec<Triangle>,
+    tolerance: f64,
+    current_triangle: usize,
+) -> Result<(), MeshError> {
+    let triangle = &triangles[current_triangle];
+    let close_edges: Vec<usize> = (0..3)
+        .filter(|&edge| {
+            let start = &triangle.vertices[edge];
+            let direction = start.segment_to(&triangle.vertices[(edge + 1) % 3]);
+            let to_point = start.segment_to(point);
+            (direction.x * to_point.y - direction.y * to_point.x).abs() / direction.norm()
+                <= tolerance
+        })
+        .collect();
+
+    let mut stack = match close_edges[..] {
+        [] => insert_triangles(point, triangles, current_triangle)?,
+        [edge] => insert_on_edge(point, triangles, current_triangle, edge)?,
+        _ => return Err(MeshError::DuplicatePoint { point: *point }),
+    };
+
+    deal_with_delaunay_condition(&mut stack, triangles, point)
+}
+
+/// Inserts a vertex in a Delaunay triangulation with the Bowyer-Watson algorithm, keeping it Delaunay.
+///
+/// The cavity made of the triangles whose circumcircle contains the point is found from the triangle containing it,
+/// by going through the adjacencies. The cavity is then replaced by triangles joining its boundary to the point:
+/// the cavity triangles are reused and two triangles are added at the end of the mesh.
+///
+/// The cavity is grown across the edges not facing the point, which rounding errors can give for nearly cocircular
+/// points. If its boundary is still not a single loop, the point is inserted with `insert_vertex` instead.
+///
+/// A point closer than the tolerance to a vertex is a duplicate.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let vertices: Vec<Point> = (0..50)
+///     .map(|i| build_point((i as f64 * 0.7).sin(), (i as f64 * 1.3).cos()))
+///     .collect();
+/// let options = TriangulationOptions {
+///     kernel: InsertionKernel::BowyerWatson,
+///     ..Default::default()
+/// };
+///
+/// let triangles = triangulate_with(&vertices, &options).unwrap().triangles;
+///
+/// assert!(validate(&vertices, &triangles, &[]).is_empty());
+/// assert_eq!(triangles.len(), triangulate(&vertices).unwrap().len());
+///
+/// // The second triangle is so flat that its circumcircle is almost the line of the edge shared with the first one:
+/// // a point on this edge is found outside of it, and the cavity has to be grown to include it
+/// let (a, b, c) = (build_point(0.1, 0.2), build_point(2.0, -1.0), build_point(3.1, 1.2));
+/// let far = build_point(1.6 - 1e16, 0.7 + 3e16);
+/// let mut triangles = vec![
+///     build_triangle([a, b, c], [Some(Neighbor::Boundary), Some(Neighbor::Boundary), Some(Neighbor::Cell(1))]),
+///     build_triangle([a, c, far], [Some(Neighbor::Cell(0)), Some(Neighbor::Boundary), Some(Neighbor::Boundary)]),
+/// ];
+/// let point = build_point(0.16, 0.22);
+/// assert!(triangles[1].circumcircle_determinant(&point) <= 0.0);
+///
+/// insert_vertex_bowyer_watson(&point, &mut triangles, 0.0).unwrap();
+///
+/// assert_eq!(triangles.len(), 4);
+/// assert!(validate(&[a, b, c, far, point], &triangles, &[]).is_empty());
+/// ```
+pub fn insert_vertex_bowyer_watson(
+    point: &Point,
+    triangles: &mut Vec<Triangle>,
+    tolerance: f64,
+) -> Result<(), MeshError> {
+    let current_triangle = locate_for_insertion(point, triangles, tolerance)?;
+    insert_vertex_bowyer_watson_in(point, triangles, tolerance, current_triangle)
+}
+
+/// Inserts a vertex with `insert_vertex_bowyer_watson`, in the triangle already found to contain it.
+pub(crate) fn insert_vertex_bowyer_watson_in(
+    point: &Point,
+    triangles: &mut Vec<Triangle>,
+    tolerance: f64,
+    current_triangle: usize,
+) -> Result<(), MeshError> {
+    if triangles[current_triangle]
+        .vertices
+        .iter()
+        .any(|vertex| vertex.segment_to(point).norm() <= tolerance)
+    {
+        return Err(MeshError::DuplicatePoint { point: *point });
+    }
+
+    let mut in_cavity = HashSet::from([current_triangle]);
+    let mut cavity = vec![current_triangle];
+    let mut stack = vec![current_triangle];
+
+    loop {
+        while let Some(id) = stack.pop() {
+            for neighbor in triangles[id].adjacencies {
+                if let Some(Neighbor::Cell(neighbor)) = neighbor {
+                    if !in_cavity.contains(&neighbor)
+                        && triangles[neighbor].circumcircle_determinant(point) > 0.0
+                    {
+                        in_cavity.insert(neighbor);
+                        cavity.push(neighbor);
+                        stack.push(neighbor);
+                    }
+                }
+            }
+        }
+
+        // Rounding errors could give a cavity which is not star-shaped from the point,
+        // the triangles behind the edges not facing the point are then added
+        for &id in &cavity {
+            let triangle = &triangles[id];
+            for edge in 0..3 {
+                if let Some(Neighbor::Cell(neighbor)) = triangle.adjacencies[edge] {
+                    let (start, end) =
+                        (&triangle.vertices[edge], &triangle.vertices[(edge + 1) % 3]);
+                    if !in_cavity.contains(&neighbor) && orientation(start, end, point) <= 0.0 {
+                        in_cavity.insert(neighbor);
+                        stack.push(neighbor);
+                    }
+                }
+            }
+        }
+        if stack.is_empty() {
+            break;
+        }
+        cavity.extend(stack.iter().copied());
+    }
 
-    for vertex in &*vertices {
+    // Edges of the boundary of the cavity, with the triangle outside of each of them
+    let mut boundary: Vec<(Point, Point, Option<Neighbor>)> = Vec::new();
+    for &id in &cavity {
+        let triangle = &triangles[id];
+        for edge in 0..3 {
+            let neighbor = triangle.adjacencies[edge];
+            if !matches!(neighbor, Some(Neighbor::Cell(neighbor)) if in_cavity.contains(&neighbor))
+            {
+                boundary.push((
+                    triangle.vertices[edge],
+                    triangle.vertices[(edge + 1) % 3],
+                    neighbor,
+                ));
+            }
+        }
+    }
+
+    // The boundary of a cavity homeomorphic to a disk is one closed loop with two more edges than the cavity has
+    // triangles. Rounding errors can give a cavity with a hole or pinched at a vertex, which would be replaced by
+    // overlapping triangles, so the point is then inserted with flips, the mesh being still untouched.
+    if boundary.len() != cavity.len() + 2 {
+        return insert_vertex_in(point, triangles, tolerance, current_triangle);
+    }
+    let next_edge: HashMap<(u64, u64), usize> = boundary
+        .iter()
+        .enumerate()
+        .map(|(i, (start, _, _))| (point_key(start), i))
+        .collect();
+    let (mut edge, mut loop_length) = (0, 1);
+    let closed = loop {
+        match next_edge.get(&point_key(&boundary[edge].1)) {
+            Some(0) => break true,
+            Some(&next) if loop_length < boundary.len() => {
+                edge = next;
+                loop_length += 1;
+            }
+            _ => break false,
+        }
+    };
+    if !closed || loop_length != boundary.len() || next_edge.len() != boundary.len() {
+        return insert_vertex_in(point, triangles, tolerance, current_triangle);
+    }
+
+    // The cavity triangles are reused first, the boundary having two more edges than the cavity has triangles
+    let mut ids = cavity;
+    while ids.len() < boundary.len() {
+        triangles.push(build_triangle([*point; 3], [None; 3]));
+        ids.push(triangles.len() - 1);
+    }
+    let starting_at: HashMap<(u64, u64), usize> = boundary
+        .iter()
+        .zip(&ids)
+        .map(|((start, _, _), id)| (point_key(start), *id))
+        .collect();
+    let ending_at: HashMap<(u64, u64), usize> = boundary
+        .iter()
+        .zip(&ids)
+        .map(|((_, end, _), id)| (point_key(end), *id))
+        .collect();
+
+    for ((start, end, neighbor), &id) in boundary.iter().zip(&ids) {
+        triangles[id] = build_triangle(
+            [*start, *end, *point],
+            [
+                *neighbor,
+                starting_at
+                    .get(&point_key(end))
+                    .map(|id| Neighbor::Cell(*id)),
+                ending_at
+                    .get(&point_key(start))
+                    .map(|id| Neighbor::Cell(*id)),
+            ],
+        );
+        set_neighbor_across(triangles, *neighbor, (start, end), Some(Neighbor::Cell(id)));
+    }
+
+    Ok(())
+}
+
+/// Finds the triangle in which a point should be inserted.
+pub(crate) fn locate_for_insertion(
+    point: &Point,
+    triangles: &[Triangle],
+    tolerance: f64,
+) -> Result<usize, MeshError> {
+    find_current_cell(point, triangles).or_else(|error| {
+        // Points on an edge can be missed because of floating point approximations
+        triangles
+            .iter()
+            .position(|triangle| {
+                let (s, t) = triangle.barycentric_coordinates_from(point);
+                s.min(t).min(1.0 - s - t) >= -tolerance
+            })
+            .ok_or(error)
+    })
+}
+
+/// Lists the vertices of the mesh once each, in the order they first appear in the triangles.
+pub fn unique_vertices(triangles: &[Triangle]) -> Vec<Point> {
+    let mut seen = HashSet::new();
+
+    triangles
+        .iter()
+        .flat_map(|triangle| triangle.vertices.iter())
+        .filter(|vertex| seen.insert(point_key(vertex)))
+        .copied()
+        .collect()
+}
+
+/// Checks if all points lie on a single line, in which case no triangle can be built.
+/// Expects rescaled vertices.
+fn are_collinear(vertices: &[Point]) -> bool {
+    let tolerance = 1e-12;
+
+    let origin = vertices[0];
+    let direction = match vertices.iter().find(|vertex| **vertex != origin) {
+        None => return true,
+        Some(vertex) => origin.segment_to(vertex),
+    };
+
+    vertices.iter().all(|vertex| {
+        let to_vertex = origin.segment_to(vertex);
+        (direction.x * to_vertex.y - direction.y * to_vertex.x).abs() <= tolerance
+    })
+}
+
+/// Computes the bounding box of the point cloud, returned as (min, max).
+pub fn bounding_box(vertices: &[Point]) -> Result<(Point, Point), MeshError> {
+    let first = vertices.first().ok_or(MeshError::EmptyInput)?;
+
+    let (mut x_max, mut x_min) = (first.x, first.x);
+    let (mut y_max, mut y_min) = (first.y, first.y);
+
+    for vertex in vertices {
         if vertex.x > x_max {
             x_max = vertex.x;
         } else if vertex.x < x_min {
@@ -23,66 +518,49 @@ pub fn rescale_vertices(vertices: &mut Vec<Point>) -> (Point, Point) {
         }
     }
 
-    let scale_factor = if x_max - x_min > y_max - y_min {
-        x_max - x_min
-    } else {
-        y_max - y_min
-    };
-
-    for vertex in &mut *vertices {
-        vertex.x = (vertex.x - x_min) / scale_factor;
-        vertex.y = (vertex.y - y_min) / scale_factor;
-    }
+    Ok((build_point(x_min, y_min), build_point(x_max, y_max)))
+}
 
-    (build_point(x_min, y_min), build_point(x_max, y_max))
+/// Checks if two boxes, given as (min, max), intersect. Boxes touching on their sides intersect.
+pub(crate) fn boxes_overlap(
+    (min_1, max_1): (&Point, &Point),
+    (min_2, max_2): (&Point, &Point),
+) -> bool {
+    min_1.x <= max_2.x && min_2.x <= max_1.x && min_1.y <= max_2.y && min_2.y <= max_1.y
 }
 
-/// Scales the point cloud back to its original size (changed by rescale_vertices)
-pub fn scale_back(
-    vertices: &mut Vec<Point>,
-    triangles: &mut Vec<Triangle>,
-    (min, max): (&Point, &Point),
-) {
+/// Rescale all vertices to make everything fit in [0, 1].
+/// Ensures for exemple the the big triangle will always be big enough.
+///
+/// Returns the original length of the point cloud to be able to scale it back
+pub fn rescale_vertices(vertices: &mut Vec<Point>) -> Result<(Point, Point), MeshError> {
+    let (min, max) = bounding_box(vertices)?;
+
     let scale_factor = if max.x - min.x > max.y - min.y {
         max.x - min.x
     } else {
         max.y - min.y
     };
 
-    for vertex in &mut *vertices {
-        vertex.x = vertex.x * scale_factor + min.x;
-        vertex.y = vertex.y * scale_factor + min.y;
+    if scale_factor <= 0.0 {
+        return Err(MeshError::DegenerateInput);
     }
 
-    for triangle in &mut *triangles {
-        for i in 0..triangle.vertices.len() {
-            triangle.vertices[i].x = triangle.vertices[i].x * scale_factor + min.x;
-            triangle.vertices[i].y = triangle.vertices[i].y * scale_factor + min.y;
-        }
+    for vertex in &mut *vertices {
+        vertex.x = (vertex.x - min.x) / scale_factor;
+        vertex.y = (vertex.y - min.y) / scale_factor;
     }
+
+    Ok((min, max))
 }
 
 /// Finds the cell in which the current point is.
-/// For now only a bruteforce (but not very efficient) algorithm is implemented.
-pub fn find_current_cell<T: Cell>(point: &Point, mesh: &Vec<T>) -> Option<usize> {
-    let mut i = 0;
-    let result;
-
-    loop {
-        if i < mesh.len() {
-            if mesh[i].include(&point) {
-                result = Some(i);
-                break;
-            } else {
-                i += 1;
-            }
-        } else {
-            result = None;
-            break;
-        }
-    }
-
-    result
+/// For now only a bruteforce (but not very efficient) algorithm is implemented,
+/// see `build_cell_tree` to locate many points in the same mesh.
+pub fn find_current_cell<T: Cell>(point: &Point, mesh: &[T]) -> Result<usize, MeshError> {
+    mesh.iter()
+        .position(|cell| cell.include(point))
+        .ok_or(MeshError::PointOutsideDomain { point: *point })
 }
 
 /// Inserts the new triangles created for the new vertex.
@@ -90,8 +568,24 @@ pub fn insert_triangles(
     point: &Point,
     triangles: &mut Vec<Triangle>,
     current_triangle: usize,
-) -> Vec<usize> {
-    let old_triangle = triangles[current_triangle].clone();
+) -> Result<Vec<usize>, MeshError> {
+    let old_triangle = match triangles.get(current_triangle) {
+        None => {
+            return Err(MeshError::TopologyCorruption {
+                cell: current_triangle,
+                reason: "cell to split does not exist",
+            })
+        }
+        Some(triangle) => *triangle,
+    };
+
+    if old_triangle
+        .find_point_in_triangle_vertices(point)
+        .is_some()
+    {
+        return Err(MeshError::DuplicatePoint { point: *point });
+    }
+
     let mut new_triangles = Vec::new();
 
     new_triangles.push(current_triangle);
@@ -163,44 +657,199 @@ pub fn insert_triangles(
         };
     }
 
-    new_triangles
+    Ok(new_triangles)
+}
+
+/// Inserts the new triangles created for a vertex lying on an edge of a triangle.
+/// The triangle and its neighbor across the edge are each split in two.
+pub fn insert_on_edge(
+    point: &Point,
+    triangles: &mut Vec<Triangle>,
+    current_triangle: usize,
+    edge: usize,
+) -> Result<Vec<usize>, MeshError> {
+    let old_triangle = match triangles.get(current_triangle) {
+        None => {
+            return Err(MeshError::TopologyCorruption {
+                cell: current_triangle,
+                reason: "cell to split does not exist",
+            })
+        }
+        Some(triangle) => *triangle,
+    };
+
+    if old_triangle
+        .find_point_in_triangle_vertices(point)
+        .is_some()
+    {
+        return Err(MeshError::DuplicatePoint { point: *point });
+    }
+
+    let a = old_triangle.vertices[edge];
+    let b = old_triangle.vertices[(edge + 1) % 3];
+    let c = old_triangle.vertices[(edge + 2) % 3];
+
+    let opposite = match old_triangle.adjacencies[edge] {
+        Some(Neighbor::Cell(id)) => {
+            let opposite_triangle = triangles[id];
+            match opposite_triangle.find_point_local_id_opposite_to(current_triangle) {
+                None => {
+                    return Err(MeshError::TopologyCorruption {
+                        cell: id,
+                        reason: "adjacency is not symmetric",
+                    })
+                }
+                Some(local_id) => Some((id, opposite_triangle, local_id)),
+            }
+        }
+        _ => None,
+    };
+
+    let first_new = triangles.len();
+    let second_new = first_new + 1;
+
+    let (outer_neighbor, shared_neighbor) = match opposite {
+        None => (
+            old_triangle.adjacencies[edge],
+            old_triangle.adjacencies[edge],
+        ),
+        Some((id, _, _)) => (Some(Neighbor::Cell(second_new)), Some(Neighbor::Cell(id))),
+    };
+
+    // (a, p, c) keeps the slot of the split triangle
+    triangles[current_triangle] = build_triangle(
+        [a, *point, c],
+        [
+            outer_neighbor,
+            Some(Neighbor::Cell(first_new)),
+            old_triangle.adjacencies[(edge + 2) % 3],
+        ],
+    );
+
+    // (p, b, c) takes the first new slot
+    triangles.push(build_triangle(
+        [*point, b, c],
+        [
+            shared_neighbor,
+            old_triangle.adjacencies[(edge + 1) % 3],
+            Some(Neighbor::Cell(current_triangle)),
+        ],
+    ));
+    replace_neighbor(
+        triangles,
+        old_triangle.adjacencies[(edge + 1) % 3],
+        current_triangle,
+        first_new,
+    );
+
+    let mut new_triangles = vec![current_triangle, first_new];
+
+    if let Some((opposite_id, opposite_triangle, d_local_id)) = opposite {
+        let d = opposite_triangle.vertices[d_local_id];
+
+        // (b, p, d) keeps the slot of the opposite triangle
+        triangles[opposite_id] = build_triangle(
+            [b, *point, d],
+            [
+                Some(Neighbor::Cell(first_new)),
+                Some(Neighbor::Cell(second_new)),
+                opposite_triangle.adjacencies[d_local_id],
+            ],
+        );
+
+        // (p, a, d) takes the second new slot
+        triangles.push(build_triangle(
+            [*point, a, d],
+            [
+                Some(Neighbor::Cell(current_triangle)),
+                opposite_triangle.adjacencies[(d_local_id + 2) % 3],
+                Some(Neighbor::Cell(opposite_id)),
+            ],
+        ));
+        replace_neighbor(
+            triangles,
+            opposite_triangle.adjacencies[(d_local_id + 2) % 3],
+            opposite_id,
+            second_new,
+        );
+
+        new_triangles.push(opposite_id);
+        new_triangles.push(second_new);
+    }
+
+    Ok(new_triangles)
+}
+
+/// Makes the neighbor point to `new_id` instead of `old_id`.
+fn replace_neighbor(
+    triangles: &mut [Triangle],
+    neighbor: Option<Neighbor>,
+    old_id: usize,
+    new_id: usize,
+) {
+    if let Some(Neighbor::Cell(id)) = neighbor {
+        for adjacency in triangles[id].adjacencies.iter_mut() {
+            if *adjacency == Some(Neighbor::Cell(old_id)) {
+                *adjacency = Some(Neighbor::Cell(new_id));
+            }
+        }
+    }
 }
 
 /// Corrects the mesh after new triangle insertion to ensure Delaunay condition remains true.
+///
+/// Fails with `MeshError::TopologyCorruption` if the adjacencies of the triangles on the stack are not consistent.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let point = build_point(0.0, 0.0);
+/// // The neighbor across the edge opposite to the point does not exist
+/// let mut triangles = vec![build_triangle(
+///     [point, build_point(1.0, 0.0), build_point(0.0, 1.0)],
+///     [None, Some(Neighbor::Cell(7)), None],
+/// )];
+///
+/// assert_eq!(
+///     deal_with_delaunay_condition(&mut vec![0], &mut triangles, &point),
+///     Err(MeshError::TopologyCorruption { cell: 0, reason: "neighbor does not exist" })
+/// );
+/// ```
 pub fn deal_with_delaunay_condition(
     stack: &mut Vec<usize>,
     triangles: &mut Vec<Triangle>,
     point: &Point,
-) {
-    if stack.is_empty() {
-        panic!("stack is empty");
-    }
+) -> Result<(), MeshError> {
+    let missing = |cell| MeshError::TopologyCorruption {
+        cell,
+        reason: "neighbor does not exist",
+    };
 
-    loop {
-        // println!("Stack : {:?}", stack);
-        // let mut _dummy = String::new();
-        // io::stdin().read_line(&mut _dummy).expect("Error in read");
+    while let Some(triangle_id) = stack.pop() {
+        let triangle = *triangles
+            .get(triangle_id)
+            .ok_or(MeshError::TopologyCorruption {
+                cell: triangle_id,
+                reason: "triangle on the stack does not exist",
+            })?;
 
-        let triangle_id = match stack.pop() {
+        let point_local_id = match triangle.find_point_in_triangle_vertices(point) {
             None => {
-                //println!("Empty stack");
-                break;
+                return Err(MeshError::TopologyCorruption {
+                    cell: triangle_id,
+                    reason: "triangle on the stack does not contain the inserted point",
+                })
             }
             Some(id) => id,
         };
 
-        let triangle = triangles[triangle_id].clone();
-
-        let point_local_id = match triangle.find_point_in_triangle_vertices(point) {
-            None => panic!("Triangle not containing current point on the stack"),
-            Some(id) => id,
-        };
-
         let (opposite_triangle, opposite_triangle_id) =
             match triangle.find_face_opposite_to(point_local_id) {
                 None => continue, //No edge swap needed
                 Some(neighbor) => match neighbor {
-                    Neighbor::Cell(id) => (triangles[id].clone(), id),
+                    Neighbor::Cell(id) => (*triangles.get(id).ok_or(missing(triangle_id))?, id),
                     Neighbor::Boundary => continue,
                 },
             };
@@ -212,15 +861,41 @@ pub fn deal_with_delaunay_condition(
 
         let opposite_point_local_id =
             match opposite_triangle.find_point_local_id_opposite_to(triangle_id) {
-                None => panic!("source triangle is not adjacent to the opposite? FF"),
+                None => {
+                    return Err(MeshError::TopologyCorruption {
+                        cell: opposite_triangle_id,
+                        reason: "adjacency is not symmetric",
+                    })
+                }
                 Some(id) => id,
             };
 
+        // The neighbors whose adjacency changes are checked before the mesh is modified
+        let outer_neighbors = [
+            (
+                opposite_triangle_id,
+                opposite_triangle.adjacencies[opposite_point_local_id],
+                triangle_id,
+            ),
+            (
+                triangle_id,
+                triangle.adjacencies[point_local_id],
+                opposite_triangle_id,
+            ),
+        ];
+        for (owner, neighbor, _) in outer_neighbors {
+            if let Some(Neighbor::Cell(id)) = neighbor {
+                if id >= triangles.len() {
+                    return Err(missing(owner));
+                }
+            }
+        }
+
         let new_triangle_1 = Triangle {
             vertices: [
-                point.clone(),
-                opposite_triangle.vertices[opposite_point_local_id].clone(),
-                triangle.vertices[(point_local_id + 2) % 3].clone(),
+                *point,
+                opposite_triangle.vertices[opposite_point_local_id],
+                triangle.vertices[(point_local_id + 2) % 3],
             ],
             adjacencies: [
                 Some(Neighbor::Cell(opposite_triangle_id)),
@@ -228,28 +903,11 @@ pub fn deal_with_delaunay_condition(
                 triangle.adjacencies[(point_local_id + 2) % 3],
             ],
         };
-
-        match opposite_triangle.adjacencies[opposite_point_local_id] {
-            None => (),
-            Some(neighbor) => match neighbor {
-                Neighbor::Boundary => (),
-                Neighbor::Cell(id) => {
-                    for i in 0..triangles[id].adjacencies.len() {
-                        if triangles[id].adjacencies[i]
-                            == Some(Neighbor::Cell(opposite_triangle_id))
-                        {
-                            triangles[id].adjacencies[i] = Some(Neighbor::Cell(triangle_id));
-                        }
-                    }
-                }
-            },
-        }
-
         let new_triangle_2 = Triangle {
             vertices: [
-                point.clone(),
-                triangle.vertices[(point_local_id + 1) % 3].clone(),
-                opposite_triangle.vertices[opposite_point_local_id].clone(),
+                *point,
+                triangle.vertices[(point_local_id + 1) % 3],
+                opposite_triangle.vertices[opposite_point_local_id],
             ],
             adjacencies: [
                 triangle.adjacencies[point_local_id],
@@ -258,19 +916,15 @@ pub fn deal_with_delaunay_condition(
             ],
         };
 
-        match triangle.adjacencies[point_local_id] {
-            None => (),
-            Some(neighbor) => match neighbor {
-                Neighbor::Boundary => (),
-                Neighbor::Cell(id) => {
-                    for i in 0..triangles[id].adjacencies.len() {
-                        if triangles[id].adjacencies[i] == Some(Neighbor::Cell(triangle_id)) {
-                            triangles[id].adjacencies[i] =
-                                Some(Neighbor::Cell(opposite_triangle_id));
-                        }
+        // Each of these neighbors now faces the other triangle of the flipped pair
+        for (owner, neighbor, new_owner) in outer_neighbors {
+            if let Some(Neighbor::Cell(id)) = neighbor {
+                for adjacency in triangles[id].adjacencies.iter_mut() {
+                    if *adjacency == Some(Neighbor::Cell(owner)) {
+                        *adjacency = Some(Neighbor::Cell(new_owner));
                     }
                 }
-            },
+            }
         }
 
         triangles[triangle_id] = new_triangle_1;
@@ -283,35 +937,787 @@ pub fn deal_with_delaunay_condition(
             stack.push(opposite_triangle_id);
         }
     }
+
+    Ok(())
 }
 
-/// The big triangle created at the start is no more needed
-pub fn remove_big_triangle(triangles: &mut Vec<Triangle>, big_triangle: &Triangle) {
-    let mut i = 0;
+/// Removes a vertex from a Delaunay triangulation and retriangulates the polygon formed by its star,
+/// so that the mesh stays Delaunay.
+///
+/// The polygon is filled by ear clipping, each ear being chosen with no other polygon vertex in its circumcircle,
+/// and never with one inside of it.
+/// On the boundary, the ears are clipped while they are convex, and the remaining edges become boundaries.
+/// Fails with `MeshError::TopologyCorruption` if no ear can be clipped, the mesh being then left unchanged.
+///
+/// Returns the indices of the new triangles. The star uses more cells than its retriangulation,
+/// so the last triangles of the mesh are moved into the freed indices.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let vertices = vec![
+///     build_point(0.0, 0.0),
+///     build_point(1.0, 0.0),
+///     build_point(1.0, 1.0),
+///     build_point(0.0, 1.0),
+///     build_point(0.4, 0.6),
+/// ];
+/// let mut mesh = triangulate(&vertices).unwrap();
+/// assert_eq!(mesh.len(), 4);
+///
+/// remove_vertex(&vertices[4], &mut mesh).unwrap();
+///
+/// assert_eq!(mesh.len(), 2);
+/// assert!(validate(&vertices[..4], &mesh, &[]).is_empty());
+///
+/// // In a fan which is not Delaunay, the ear closest to being Delaunay can contain another vertex of the polygon,
+/// // it is then skipped so that the new triangles do not overlap
+/// let polygon: Vec<Point> = [
+///     (0.3, 0.0), (0.3, 0.3), (0.0, 0.3), (-0.8, 2.0), (-0.1, 0.0),
+///     (-2.3, -0.4), (-0.5, -0.4), (-0.2, -0.8), (0.2, -0.6), (1.0, -0.6),
+/// ]
+/// .iter()
+/// .map(|(x, y)| build_point(*x, *y))
+/// .collect();
+/// let center = build_point(0.0, 0.0);
+/// let mut fan: Vec<Triangle> = (0..10)
+///     .map(|i| {
+///         build_triangle(
+///             [center, polygon[i], polygon[(i + 1) % 10]],
+///             [Some(Neighbor::Cell((i + 9) % 10)), Some(Neighbor::Boundary), Some(Neighbor::Cell((i + 1) % 10))],
+///         )
+///     })
+///     .collect();
+///
+/// remove_vertex(&center, &mut fan).unwrap();
+///
+/// assert_eq!(fan.len(), 8);
+/// assert!(fan.iter().all(|triangle| triangle.signed_area() > 0.0));
+/// let area: f64 = fan.iter().map(|triangle| triangle.signed_area()).sum();
+/// assert!((area - build_polygon(polygon, vec![None; 10]).signed_area()).abs() < 1e-12);
+/// ```
+pub fn remove_vertex(
+    point: &Point,
+    triangles: &mut Vec<Triangle>,
+) -> Result<Vec<usize>, MeshError> {
+    let start = triangles
+        .iter()
+        .position(|triangle| triangle.find_point_in_triangle_vertices(point).is_some())
+        .ok_or(MeshError::VertexNotFound { point: *point })?;
+
+    remove_vertex_from(point, triangles, start).map(|removal| removal.created)
+}
+
+/// Cells changed by `remove_vertex_from`.
+pub(crate) struct VertexRemoval {
+    /// Triangles around the removed vertex, with their indices before the removal.
+    pub(crate) star: Vec<usize>,
+    /// New triangles, with their indices after the removal.
+    pub(crate) created: Vec<usize>,
+    /// Other triangles moved into the freed indices, as (index before, index after).
+    pub(crate) moved: Vec<(usize, usize)>,
+}
+
+/// Removes a vertex with `remove_vertex`, starting from a triangle containing it.
+pub(crate) fn remove_vertex_from(
+    point: &Point,
+    triangles: &mut Vec<Triangle>,
+    start: usize,
+) -> Result<VertexRemoval, MeshError> {
+    let star = vertex_star(point, triangles, start)?;
+
+    // Polygon around the vertex, counter-clockwise, with for each edge what is on the other side
+    let closed = star.closed;
+    let mut ring: Vec<Point> = Vec::new();
+    let mut ring_edges: Vec<Option<Neighbor>> = Vec::new();
+    for &id in &star.cells {
+        let triangle = &triangles[id];
+        let local_id = triangle.find_point_in_triangle_vertices(point).unwrap_or(0);
+        ring.push(triangle.vertices[(local_id + 1) % 3]);
+        ring_edges.push(triangle.adjacencies[(local_id + 1) % 3]);
+    }
+    if !closed {
+        let last = &triangles[star.cells[star.cells.len() - 1]];
+        let local_id = last.find_point_in_triangle_vertices(point).unwrap_or(0);
+        ring.push(last.vertices[(local_id + 2) % 3]);
+    }
+
+    // Edges still to be covered, each given by what lies outside of it
+    let mut outside: Vec<EdgeSide> = ring_edges.into_iter().map(EdgeSide::Outer).collect();
+    let mut new_triangles: Vec<([Point; 3], [EdgeSide; 3])> = Vec::new();
+    let cannot_retriangulate = || MeshError::TopologyCorruption {
+        cell: start,
+        reason: "star of the vertex cannot be retriangulated",
+    };
 
     loop {
-        if i >= triangles.len() {
+        let count = ring.len();
+        if count < 3 {
+            break;
+        }
+        if closed && count == 3 {
+            if build_triangle([ring[0], ring[1], ring[2]], [None; 3]).signed_area() <= 0.0 {
+                return Err(cannot_retriangulate());
+            }
+            new_triangles.push((
+                [ring[0], ring[1], ring[2]],
+                [outside[0], outside[1], outside[2]],
+            ));
+            let new_id = new_triangles.len() - 1;
+            for (edge, side) in outside.iter().enumerate() {
+                link_new_edge(&mut new_triangles, *side, new_id, edge);
+            }
             break;
         }
 
-        if (big_triangle.find_point_in_triangle_vertices(&triangles[i].vertices[0]) != None)
-            | (big_triangle.find_point_in_triangle_vertices(&triangles[i].vertices[1]) != None)
-            | (big_triangle.find_point_in_triangle_vertices(&triangles[i].vertices[2]) != None)
-        {
-            for k in 0..triangles.len() {
-                for j in 0..3 {
-                    if triangles[k].adjacencies[j] == Some(Neighbor::Cell(i)) {
-                        triangles[k].adjacencies[j] = None;
-                    } else if triangles[k].adjacencies[j]
-                        == Some(Neighbor::Cell(triangles.len() - 1))
-                    {
-                        triangles[k].adjacencies[j] = Some(Neighbor::Cell(i));
-                    }
+        let positions: Vec<usize> = if closed {
+            (0..count).collect()
+        } else {
+            (1..count - 1).collect()
+        };
+
+        let ear = |j: usize| {
+            let (prev, next) = ((j + count - 1) % count, (j + 1) % count);
+            build_triangle([ring[prev], ring[j], ring[next]], [None; 3])
+        };
+
+        // Convex ears with no other vertex inside or on them, sorted by how much the other vertices violate
+        // their circumcircle
+        let mut best: Option<(usize, f64)> = None;
+        let mut convex = false;
+        for &j in &positions {
+            let candidate = ear(j);
+            if candidate.signed_area() <= 0.0 {
+                continue;
+            }
+            convex = true;
+            let others = ring
+                .iter()
+                .filter(|vertex| candidate.find_point_in_triangle_vertices(vertex).is_none());
+            if others.clone().any(|vertex| candidate.include(vertex)) {
+                continue;
+            }
+            let violation = others
+                .map(|vertex| candidate.circumcircle_determinant(vertex))
+                .fold(f64::MIN, f64::max);
+            if best.is_none_or(|(_, best_violation)| violation < best_violation) {
+                best = Some((j, violation));
+            }
+            if violation <= 0.0 {
+                break;
+            }
+        }
+
+        let j = match best {
+            Some((j, _)) => j,
+            None if closed || convex => return Err(cannot_retriangulate()),
+            // What remains of an open chain is convex and becomes the boundary
+            None => break,
+        };
+
+        let (prev, next) = ((j + count - 1) % count, (j + 1) % count);
+        new_triangles.push((
+            [ring[prev], ring[j], ring[next]],
+            [outside[prev], outside[j], EdgeSide::Boundary],
+        ));
+        let new_id = new_triangles.len() - 1;
+        link_new_edge(&mut new_triangles, outside[prev], new_id, 0);
+        link_new_edge(&mut new_triangles, outside[j], new_id, 1);
+
+        outside[prev] = EdgeSide::New(new_id, 2);
+        outside.remove(j);
+        ring.remove(j);
+    }
+
+    // Edges of the open chain left uncovered are now on the boundary
+    if !closed {
+        for (edge, side) in outside.iter().enumerate() {
+            match side {
+                EdgeSide::Outer(neighbor) => set_neighbor_across(
+                    triangles,
+                    *neighbor,
+                    (&ring[edge], &ring[edge + 1]),
+                    Some(Neighbor::Boundary),
+                ),
+                EdgeSide::New(id, local_edge) => {
+                    new_triangles[*id].1[*local_edge] = EdgeSide::Boundary
+                }
+                EdgeSide::Boundary => (),
+            }
+        }
+    }
+
+    // The new triangles take the first slots of the star
+    let slots = &star.cells[..new_triangles.len()];
+    for (new_id, (vertices, sides)) in new_triangles.iter().enumerate() {
+        let mut adjacencies = [None; 3];
+        for edge in 0..3 {
+            adjacencies[edge] = match sides[edge] {
+                EdgeSide::Outer(neighbor) => {
+                    set_neighbor_across(
+                        triangles,
+                        neighbor,
+                        (&vertices[edge], &vertices[(edge + 1) % 3]),
+                        Some(Neighbor::Cell(slots[new_id])),
+                    );
+                    neighbor
+                }
+                EdgeSide::New(id, _) => Some(Neighbor::Cell(slots[id])),
+                EdgeSide::Boundary => Some(Neighbor::Boundary),
+            };
+        }
+        triangles[slots[new_id]] = build_triangle(*vertices, adjacencies);
+    }
+
+    let mut result = slots.to_vec();
+
+    // Index before the removal of each triangle moved, by its current index
+    let mut moved_from: HashMap<usize, usize> = HashMap::new();
+    let mut freed = star.cells[new_triangles.len()..].to_vec();
+    freed.sort_unstable_by(|a, b| b.cmp(a));
+    for id in freed {
+        if let Some(moved) = remove_triangle(triangles, id) {
+            for new_id in result.iter_mut() {
+                if *new_id == moved {
+                    *new_id = id;
                 }
             }
-            triangles.swap_remove(i);
+            let from = moved_from.remove(&moved).unwrap_or(moved);
+            moved_from.insert(id, from);
+        }
+    }
+
+    let mut moved: Vec<(usize, usize)> = moved_from
+        .into_iter()
+        .filter(|(_, from)| !slots.contains(from))
+        .map(|(to, from)| (from, to))
+        .collect();
+    moved.sort_unstable();
+
+    Ok(VertexRemoval {
+        star: star.cells,
+        created: result,
+        moved,
+    })
+}
+
+/// What lies on the other side of an edge while filling a polygon.
+#[derive(Debug, Clone, Copy)]
+enum EdgeSide {
+    /// A triangle of the mesh outside of the polygon.
+    Outer(Option<Neighbor>),
+    /// One of the new triangles, with the index of the edge in it.
+    New(usize, usize),
+    Boundary,
+}
+
+/// Makes the edge of a new triangle point back to the new triangle on its other side.
+fn link_new_edge(
+    new_triangles: &mut [([Point; 3], [EdgeSide; 3])],
+    side: EdgeSide,
+    new_id: usize,
+    edge: usize,
+) {
+    if let EdgeSide::New(id, local_edge) = side {
+        new_triangles[id].1[local_edge] = EdgeSide::New(new_id, edge);
+    }
+}
+
+/// Triangles around a vertex, counter-clockwise.
+pub(crate) struct Star {
+    pub(crate) cells: Vec<usize>,
+    /// False if the vertex is on the boundary, the first and last cells then have a boundary edge.
+    pub(crate) closed: bool,
+}
+
+/// Finds the triangles around a vertex by going through adjacencies, starting from one of them.
+pub(crate) fn vertex_star(
+    point: &Point,
+    triangles: &[Triangle],
+    start: usize,
+) -> Result<Star, MeshError> {
+    let corrupted = |cell| MeshError::TopologyCorruption {
+        cell,
+        reason: "triangle around the vertex does not contain it",
+    };
+
+    // Going clockwise to find the first triangle if the vertex is on the boundary
+    let mut first = start;
+    for _ in 0..triangles.len() {
+        let triangle = &triangles[first];
+        let local_id = triangle
+            .find_point_in_triangle_vertices(point)
+            .ok_or(corrupted(first))?;
+        match triangle.adjacencies[local_id] {
+            Some(Neighbor::Cell(id)) if id != start => first = id,
+            Some(Neighbor::Cell(_)) => return star_from(point, triangles, start, true),
+            _ => return star_from(point, triangles, first, false),
+        }
+    }
+
+    Err(MeshError::TopologyCorruption {
+        cell: start,
+        reason: "star of the vertex does not close",
+    })
+}
+
+/// Goes counter-clockwise around the vertex from the given triangle.
+fn star_from(
+    point: &Point,
+    triangles: &[Triangle],
+    first: usize,
+    closed: bool,
+) -> Result<Star, MeshError> {
+    let mut cells = vec![first];
+    let mut current = first;
+
+    loop {
+        let triangle = &triangles[current];
+        let local_id = triangle.find_point_in_triangle_vertices(point).ok_or(
+            MeshError::TopologyCorruption {
+                cell: current,
+                reason: "triangle around the vertex does not contain it",
+            },
+        )?;
+        match triangle.adjacencies[(local_id + 2) % 3] {
+            Some(Neighbor::Cell(id)) if id == first => break,
+            Some(Neighbor::Cell(id)) => {
+                if cells.len() > triangles.len() {
+                    return Err(MeshError::TopologyCorruption {
+                        cell: id,
+                        reason: "star of the vertex does not close",
+                    });
+                }
+                cells.push(id);
+                current = id;
+            }
+            _ => break,
+        }
+    }
+
+    Ok(Star { cells, closed })
+}
+
+/// Sets the adjacency of the neighbor on its edge going from `end` to `start`.
+fn set_neighbor_across(
+    triangles: &mut [Triangle],
+    neighbor: Option<Neighbor>,
+    (start, end): (&Point, &Point),
+    value: Option<Neighbor>,
+) {
+    if let Some(Neighbor::Cell(id)) = neighbor {
+        let triangle = &mut triangles[id];
+        for edge in 0..3 {
+            if &triangle.vertices[edge] == end && &triangle.vertices[(edge + 1) % 3] == start {
+                triangle.adjacencies[edge] = value;
+            }
+        }
+    }
+}
+
+/// Removes a triangle no other triangle points to, by moving the last triangle in its place.
+/// Returns the former index of the moved triangle.
+fn remove_triangle(triangles: &mut Vec<Triangle>, id: usize) -> Option<usize> {
+    let last = triangles.len() - 1;
+    triangles.swap_remove(id);
+
+    if id == last {
+        return None;
+    }
+
+    for neighbor in triangles[id].adjacencies {
+        replace_neighbor(triangles, neighbor, last, id);
+    }
+
+    Some(last)
+}
+
+/// Flips the edge shared by a triangle and its neighbor: the two triangles forming the quad (a, d, b, c)
+/// across the edge (a, b) become (a, d, c) and (d, b, c). The two triangles keep their indices.
+///
+/// The quad is expected to be convex.
+pub fn flip_edge(
+    triangles: &mut [Triangle],
+    triangle_id: usize,
+    edge: usize,
+) -> Result<(), MeshError> {
+    let triangle = triangles[triangle_id];
+
+    let opposite_id = match triangle.adjacencies[edge] {
+        Some(Neighbor::Cell(id)) => id,
+        _ => {
+            return Err(MeshError::TopologyCorruption {
+                cell: triangle_id,
+                reason: "boundary edge cannot be flipped",
+            })
+        }
+    };
+    let opposite = triangles[opposite_id];
+    let d_local_id = opposite
+        .find_point_local_id_opposite_to(triangle_id)
+        .ok_or(MeshError::TopologyCorruption {
+            cell: opposite_id,
+            reason: "adjacency is not symmetric",
+        })?;
+
+    let a = triangle.vertices[edge];
+    let b = triangle.vertices[(edge + 1) % 3];
+    let c = triangle.vertices[(edge + 2) % 3];
+    let d = opposite.vertices[d_local_id];
+
+    let (b_to_c, c_to_a) = (
+        triangle.adjacencies[(edge + 1) % 3],
+        triangle.adjacencies[(edge + 2) % 3],
+    );
+    let (a_to_d, d_to_b) = (
+        opposite.adjacencies[(d_local_id + 2) % 3],
+        opposite.adjacencies[d_local_id],
+    );
+
+    triangles[triangle_id] = build_triangle(
+        [a, d, c],
+        [a_to_d, Some(Neighbor::Cell(opposite_id)), c_to_a],
+    );
+    triangles[opposite_id] = build_triangle(
+        [d, b, c],
+        [d_to_b, b_to_c, Some(Neighbor::Cell(triangle_id))],
+    );
+
+    set_neighbor_across(
+        triangles,
+        a_to_d,
+        (&a, &d),
+        Some(Neighbor::Cell(triangle_id)),
+    );
+    set_neighbor_across(
+        triangles,
+        b_to_c,
+        (&b, &c),
+        Some(Neighbor::Cell(opposite_id)),
+    );
+
+    Ok(())
+}
+
+/// Makes an edge between two vertices of the mesh appear, by flipping the edges crossing it.
+/// This is needed to enforce boundaries the Delaunay triangulation does not contain.
+///
+/// The crossed edges are found by walking through the adjacencies from the triangles around `start`,
+/// then flipped in turn until none remain.
+///
+/// Fails if the segment goes through another vertex.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let vertices: Vec<Point> = (0..300)
+///     .map(|i| build_point((i as f64 * 0.7).sin(), (i as f64 * 1.3).cos()))
+///     .collect();
+///
+/// // Long segments across the mesh cross many edges
+/// for k in 0..10 {
+///     let mut triangles = triangulate(&vertices).unwrap();
+///     let (start, end) = (vertices[k], vertices[299 - k]);
+///     recover_edge(&start, &end, &mut triangles).unwrap();
+///
+///     assert!(triangles.iter().any(|triangle| {
+///         triangle.find_point_in_triangle_vertices(&start).is_some()
+///             && triangle.find_point_in_triangle_vertices(&end).is_some()
+///     }));
+///     assert!(validate(&unique_vertices(&triangles), &triangles, &[])
+///         .iter()
+///         .all(|issue| matches!(issue, MeshIssue::NotDelaunay { .. })));
+/// }
+/// ```
+pub fn recover_edge(
+    start: &Point,
+    end: &Point,
+    triangles: &mut [Triangle],
+) -> Result<(), MeshError> {
+    let crosses = |u: &Point, v: &Point| {
+        orientation(start, end, u) * orientation(start, end, v) < 0.0
+            && orientation(u, v, start) * orientation(u, v, end) < 0.0
+    };
+    let cannot_recover = || MeshError::TopologyCorruption {
+        cell: 0,
+        reason: "edge cannot be recovered",
+    };
+
+    let first = triangles
+        .iter()
+        .position(|triangle| triangle.find_point_in_triangle_vertices(start).is_some())
+        .ok_or_else(cannot_recover)?;
+    let star = vertex_star(start, triangles, first)?;
+    if star.cells.iter().any(|id| {
+        triangles[*id]
+            .find_point_in_triangle_vertices(end)
+            .is_some()
+    }) {
+        return Ok(());
+    }
+
+    // The edges crossed by the segment, walking from the triangle of the star it goes through to the end
+    let mut crossed_cells = Vec::new();
+    let mut queue = VecDeque::new();
+    let mut current = star.cells.iter().find_map(|id| {
+        let triangle = &triangles[*id];
+        let edge = (triangle.find_point_in_triangle_vertices(start)? + 1) % 3;
+        crosses(&triangle.vertices[edge], &triangle.vertices[(edge + 1) % 3]).then_some((*id, edge))
+    });
+    while let Some((id, edge)) = current {
+        if crossed_cells.len() > triangles.len() {
+            return Err(cannot_recover());
+        }
+        crossed_cells.push(id);
+        queue.push_back((
+            triangles[id].vertices[edge],
+            triangles[id].vertices[(edge + 1) % 3],
+        ));
+
+        let Some(Neighbor::Cell(neighbor)) = triangles[id].adjacencies[edge] else {
+            return Err(cannot_recover());
+        };
+        let next = &triangles[neighbor];
+        let entry = (0..3)
+            .find(|local_edge| next.adjacencies[*local_edge] == Some(Neighbor::Cell(id)))
+            .ok_or(MeshError::TopologyCorruption {
+                cell: neighbor,
+                reason: "adjacency is not symmetric",
+            })?;
+        if next.vertices[(entry + 2) % 3] == *end {
+            crossed_cells.push(neighbor);
+            break;
+        }
+        current = [(entry + 1) % 3, (entry + 2) % 3]
+            .into_iter()
+            .find(|local_edge| {
+                crosses(
+                    &next.vertices[*local_edge],
+                    &next.vertices[(local_edge + 1) % 3],
+                )
+            })
+            .map(|local_edge| (neighbor, local_edge));
+        // The segment goes through a vertex
+        if current.is_none() {
+            return Err(cannot_recover());
+        }
+    }
+
+    // Each crossed edge is flipped in turn if its quad is convex. The edges which cannot be flipped yet,
+    // and the new ones still crossing, go to the back of the queue, which always empties (Sloan's algorithm).
+    // The flips only change the crossed triangles.
+    let mut remaining = crossed_cells.len().pow(3) + 1;
+    while let Some((u, v)) = queue.pop_front() {
+        remaining = remaining.checked_sub(1).ok_or_else(cannot_recover)?;
+
+        let (id, edge) = crossed_cells
+            .iter()
+            .find_map(|id| {
+                let triangle = &triangles[*id];
+                (0..3)
+                    .find(|edge| {
+                        triangle.vertices[*edge] == u && triangle.vertices[(edge + 1) % 3] == v
+                    })
+                    .map(|edge| (*id, edge))
+            })
+            .ok_or_else(cannot_recover)?;
+        let triangle = &triangles[id];
+        let (a, b, c) = (
+            triangle.vertices[edge],
+            triangle.vertices[(edge + 1) % 3],
+            triangle.vertices[(edge + 2) % 3],
+        );
+        let Some(Neighbor::Cell(neighbor)) = triangle.adjacencies[edge] else {
+            return Err(cannot_recover());
+        };
+        let d = match triangles[neighbor].find_point_local_id_opposite_to(id) {
+            Some(d_local_id) => triangles[neighbor].vertices[d_local_id],
+            None => return Err(cannot_recover()),
+        };
+
+        if orientation(&a, &d, &c) > 0.0 && orientation(&d, &b, &c) > 0.0 {
+            flip_edge(triangles, id, edge)?;
+            if crosses(&d, &c) {
+                queue.push_back((d, c));
+            }
         } else {
-            i += 1;
+            queue.push_back((u, v));
+        }
+    }
+
+    Ok(())
+}
+
+/// Flips every edge which is not locally Delaunay until none remain (Lawson's algorithm),
+/// and returns the number of flips.
+///
+/// Works on any valid triangulation, not only those built by `triangulate`.
+/// Boundary edges and the edges in `constrained_edges` are never flipped,
+/// so the result is only constrained Delaunay if there are constrained edges.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let (a, b, c, d) = (
+///     build_point(0.0, 0.0),
+///     build_point(1.0, -0.2),
+///     build_point(2.0, 0.0),
+///     build_point(1.0, 0.2),
+/// );
+/// let boundary = Some(Neighbor::Boundary);
+/// // The long diagonal of the rhombus is only kept if it is constrained
+/// let mut triangles = vec![
+///     build_triangle([a, b, c], [boundary, boundary, Some(Neighbor::Cell(1))]),
+///     build_triangle([a, c, d], [Some(Neighbor::Cell(0)), boundary, boundary]),
+/// ];
+///
+/// assert_eq!(delaunay_flips(&mut triangles, &[(a, c)]).unwrap(), 0);
+/// assert_eq!(delaunay_flips(&mut triangles, &[]).unwrap(), 1);
+/// assert!(validate(&[a, b, c, d], &triangles, &[]).is_empty());
+///
+/// // The short diagonal is Delaunay, so nothing changes anymore
+/// assert_eq!(delaunay_flips(&mut triangles, &[]).unwrap(), 0);
+/// ```
+pub fn delaunay_flips(
+    triangles: &mut [Triangle],
+    constrained_edges: &[(Point, Point)],
+) -> Result<usize, MeshError> {
+    let constrained: HashSet<_> = constrained_edges
+        .iter()
+        .map(|(a, b)| edge_key(a, b))
+        .collect();
+
+    let mut stack: Vec<(usize, usize)> = (0..triangles.len())
+        .flat_map(|id| (0..3).map(move |edge| (id, edge)))
+        .collect();
+    let mut flips = 0;
+
+    while let Some((id, edge)) = stack.pop() {
+        let triangle = &triangles[id];
+        let neighbor = match triangle.adjacencies[edge] {
+            Some(Neighbor::Cell(neighbor)) => neighbor,
+            _ => continue,
+        };
+        let (a, b, c) = (
+            &triangle.vertices[edge],
+            &triangle.vertices[(edge + 1) % 3],
+            &triangle.vertices[(edge + 2) % 3],
+        );
+        if constrained.contains(&edge_key(a, b)) {
+            continue;
+        }
+        let d = match triangles[neighbor].find_point_local_id_opposite_to(id) {
+            Some(local_id) => &triangles[neighbor].vertices[local_id],
+            None => {
+                return Err(MeshError::TopologyCorruption {
+                    cell: neighbor,
+                    reason: "adjacency is not symmetric",
+                })
+            }
+        };
+
+        let scale = triangle
+            .vertices
+            .iter()
+            .map(|vertex| d.segment_to(vertex).norm())
+            .fold(0.0, f64::max)
+            .powi(4);
+        let is_convex = orientation(a, d, c) > 0.0 && orientation(d, b, c) > 0.0;
+
+        if is_convex && triangle.circumcircle_determinant(d) > DELAUNAY_TOLERANCE * scale {
+            flip_edge(triangles, id, edge)?;
+            flips += 1;
+            // The edges of the quad may not be locally Delaunay anymore
+            stack.extend((0..3).flat_map(|edge| [(id, edge), (neighbor, edge)]));
+        }
+    }
+
+    Ok(flips)
+}
+
+/// Twice the signed area of the triangle (a, b, c), positive if it is counter-clockwise.
+pub(crate) fn orientation(a: &Point, b: &Point, c: &Point) -> f64 {
+    let (ab, ac) = (a.segment_to(b), a.segment_to(c));
+    ab.x * ac.y - ab.y * ac.x
+}
+
+/// The big triangle created at the start is no more needed.
+///
+/// Every triangle using one of its vertices is dropped in a single pass: the remaining triangles keep their order,
+/// their indices are remapped with a lookup table, and the edges left without neighbor become `Neighbor::Boundary`.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let big_triangle = build_triangle(
+///     [build_point(-100.0, -100.0), build_point(100.0, 0.0), build_point(0.0, 100.0)],
+///     [None; 3],
+/// );
+/// let mut triangles = vec![big_triangle];
+/// for i in 0..=4 {
+///     for j in 0..=4 {
+///         insert_vertex(&build_point(i as f64 / 4.0, j as f64 / 4.0), &mut triangles, 1e-10).unwrap();
+///     }
+/// }
+///
+/// remove_big_triangle(&mut triangles, &big_triangle);
+/// assert_eq!(triangles.len(), 2 * 4 * 4);
+///
+/// // The edges on the sides of the square are on the boundary, the others have a valid neighbor
+/// let on_side = |a: &Point, b: &Point| {
+///     (a.x == b.x && (a.x == 0.0 || a.x == 1.0)) || (a.y == b.y && (a.y == 0.0 || a.y == 1.0))
+/// };
+/// for triangle in &triangles {
+///     for edge in 0..3 {
+///         let (a, b) = (&triangle.vertices[edge], &triangle.vertices[(edge + 1) % 3]);
+///         match triangle.adjacencies[edge] {
+///             Some(Neighbor::Boundary) => assert!(on_side(a, b)),
+///             Some(Neighbor::Cell(id)) => assert!(id < triangles.len() && !on_side(a, b)),
+///             None => panic!("edge without neighbor"),
+///         }
+///     }
+/// }
+/// ```
+pub fn remove_big_triangle(triangles: &mut Vec<Triangle>, big_triangle: &Triangle) {
+    let mut new_ids: Vec<Option<usize>> = Vec::with_capacity(triangles.len());
+    let mut count = 0;
+
+    for triangle in triangles.iter() {
+        if triangle.vertices.iter().any(|vertex| {
+            big_triangle
+                .find_point_in_triangle_vertices(vertex)
+                .is_some()
+        }) {
+            new_ids.push(None);
+        } else {
+            new_ids.push(Some(count));
+            count += 1;
+        }
+    }
+
+    let mut id = 0;
+    triangles.retain(|_| {
+        id += 1;
+        new_ids[id - 1].is_some()
+    });
+
+    for triangle in triangles.iter_mut() {
+        for adjacency in triangle.adjacencies.iter_mut() {
+            *adjacency = match *adjacency {
+                Some(Neighbor::Cell(old_id)) => match new_ids[old_id] {
+                    Some(new_id) => Some(Neighbor::Cell(new_id)),
+                    None => Some(Neighbor::Boundary),
+                },
+                _ => Some(Neighbor::Boundary),
+            };
         }
     }
 }
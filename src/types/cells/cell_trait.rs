@@ -1,3 +1,6 @@
+//used to draw the cells (test purpose)
+use flo_canvas::*;
+
 use crate::types::base::*;
 
 /// This trait defines the methods needed for each cell.
@@ -21,4 +24,66 @@ pub trait Cell {
     /// Gives the siged area of the cell.
     /// Is positive if the vertices are defined counter-clockwise.
     fn signed_area(&self) -> f64;
+
+    /// Gives the interior angle at each vertex of the cell, in radians.
+    /// Expects the vertices to be counter-clockwise.
+    fn angles(&self) -> Vec<f64> {
+        let vertices: Vec<&Point> = self.iter_vertices().collect();
+        let count = vertices.len();
+
+        (0..count)
+            .map(|i| {
+                let to_previous = vertices[i].segment_to(vertices[(i + count - 1) % count]);
+                let to_next = vertices[i].segment_to(vertices[(i + 1) % count]);
+                let cross = to_next.x * to_previous.y - to_next.y * to_previous.x;
+                let angle = cross.atan2(&to_next * &to_previous);
+                if angle < 0.0 {
+                    angle + 2.0 * std::f64::consts::PI
+                } else {
+                    angle
+                }
+            })
+            .collect()
+    }
+
+    /// Gives the quality of the cell from its equiangle skewness, between 0 (degenerate) and 1 (regular polygon).
+    fn quality(&self) -> f64 {
+        let angles = self.angles();
+        let count = angles.len() as f64;
+        let ideal = (count - 2.0) * std::f64::consts::PI / count;
+
+        let max = angles.iter().copied().fold(f64::MIN, f64::max);
+        let min = angles.iter().copied().fold(f64::MAX, f64::min);
+        let skewness = ((max - ideal) / (std::f64::consts::PI - ideal)).max((ideal - min) / ideal);
+
+        (1.0 - skewness).clamp(0.0, 1.0)
+    }
+
+    /// Draw the cell using flo_canvas, for debug purpose
+    fn draw(&self, window_dimension: &(Point, Point), canvas: &DrawingTarget, line_color: &Color) {
+        let vertices: Vec<Point> = self.iter_vertices().copied().collect();
+
+        canvas.draw(|gc| {
+            // Set up the canvas
+            gc.canvas_height((window_dimension.1.x - window_dimension.0.x) as f32);
+            gc.center_region(
+                window_dimension.0.x as f32,
+                window_dimension.0.y as f32,
+                window_dimension.1.x as f32,
+                window_dimension.1.y as f32,
+            );
+
+            gc.new_path();
+            gc.move_to(vertices[0].x as f32, vertices[0].y as f32);
+            for vertex in vertices.iter().skip(1) {
+                gc.line_to(vertex.x as f32, vertex.y as f32);
+            }
+            gc.line_to(vertices[0].x as f32, vertices[0].y as f32);
+
+            let width_factor: f32 = 0.001;
+            gc.line_width(width_factor * (window_dimension.1.x - window_dimension.0.x) as f32);
+            gc.stroke_color(*line_color);
+            gc.stroke();
+        });
+    }
 }
@@ -0,0 +1,94 @@
+use crate::types::cells::cell_trait::*;
+use crate::types::cells::polygon::*;
+use crate::types::cells::quad::*;
+use crate::types::cells::triangle::*;
+
+use crate::types::base::*;
+
+/// Any cell shape, so that different shapes can be stored in the same mesh.
+/// Calls to the `Cell` methods are dispatched to the underlying shape.
+#[derive(Debug, Clone)]
+pub enum MeshCell {
+    Triangle(Triangle),
+    Quad(Quad),
+    Polygon(Polygon),
+}
+
+impl Cell for MeshCell {
+    fn include(&self, point: &Point) -> bool {
+        match self {
+            MeshCell::Triangle(triangle) => triangle.include(point),
+            MeshCell::Quad(quad) => quad.include(point),
+            MeshCell::Polygon(polygon) => polygon.include(point),
+        }
+    }
+
+    fn iter_vertices(&self) -> std::slice::Iter<'_, Point> {
+        match self {
+            MeshCell::Triangle(triangle) => triangle.iter_vertices(),
+            MeshCell::Quad(quad) => quad.iter_vertices(),
+            MeshCell::Polygon(polygon) => polygon.iter_vertices(),
+        }
+    }
+
+    fn iter_adjacencies(&self) -> std::slice::Iter<'_, Option<Neighbor>> {
+        match self {
+            MeshCell::Triangle(triangle) => triangle.iter_adjacencies(),
+            MeshCell::Quad(quad) => quad.iter_adjacencies(),
+            MeshCell::Polygon(polygon) => polygon.iter_adjacencies(),
+        }
+    }
+
+    fn center(&self) -> Point {
+        match self {
+            MeshCell::Triangle(triangle) => triangle.center(),
+            MeshCell::Quad(quad) => quad.center(),
+            MeshCell::Polygon(polygon) => polygon.center(),
+        }
+    }
+
+    fn normals(&self) -> Vec<Vector> {
+        match self {
+            MeshCell::Triangle(triangle) => triangle.normals(),
+            MeshCell::Quad(quad) => quad.normals(),
+            MeshCell::Polygon(polygon) => polygon.normals(),
+        }
+    }
+
+    fn signed_area(&self) -> f64 {
+        match self {
+            MeshCell::Triangle(triangle) => triangle.signed_area(),
+            MeshCell::Quad(quad) => quad.signed_area(),
+            MeshCell::Polygon(polygon) => polygon.signed_area(),
+        }
+    }
+}
+
+impl MeshCell {
+    /// Gives the neighbors of the cell, to be modified
+    pub fn adjacencies_mut(&mut self) -> &mut [Option<Neighbor>] {
+        match self {
+            MeshCell::Triangle(triangle) => &mut triangle.adjacencies,
+            MeshCell::Quad(quad) => &mut quad.adjacencies,
+            MeshCell::Polygon(polygon) => &mut polygon.adjacencies,
+        }
+    }
+}
+
+impl From<Triangle> for MeshCell {
+    fn from(triangle: Triangle) -> Self {
+        MeshCell::Triangle(triangle)
+    }
+}
+
+impl From<Quad> for MeshCell {
+    fn from(quad: Quad) -> Self {
+        MeshCell::Quad(quad)
+    }
+}
+
+impl From<Polygon> for MeshCell {
+    fn from(polygon: Polygon) -> Self {
+        MeshCell::Polygon(polygon)
+    }
+}
@@ -1,7 +1,13 @@
 //! Provides every cell type and the trait they should all use.
 
+pub use mesh_cell::*;
+pub use polygon::*;
+pub use quad::*;
 pub use triangle::*;
 
+pub mod mesh_cell;
+pub mod polygon;
+pub mod quad;
 pub mod triangle;
 
 pub mod cell_trait;
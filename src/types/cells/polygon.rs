@@ -0,0 +1,127 @@
+use crate::types::cells::cell_trait::*;
+
+use crate::types::base::*;
+
+/// Definition of a cell with any number of vertices.
+/// Vertices are counter-clockwise, and `adjacencies[i]` is the neighbor across the edge from `vertices[i]` to `vertices[i + 1]`.
+#[derive(Debug, Clone)]
+pub struct Polygon {
+    pub vertices: Vec<Point>,
+    pub adjacencies: Vec<Option<Neighbor>>,
+}
+
+impl Cell for Polygon {
+    /// Checks if the point is in this cell, points on the edges being included.
+    /// Works for non-convex polygons by computing the winding number of the point.
+    fn include(&self, point: &Point) -> bool {
+        let count = self.vertices.len();
+        let mut winding_number = 0;
+
+        for i in 0..count {
+            let (start, end) = (&self.vertices[i], &self.vertices[(i + 1) % count]);
+            let edge = start.segment_to(end);
+            let to_point = start.segment_to(point);
+            let cross = edge.x * to_point.y - edge.y * to_point.x;
+
+            // On the edge
+            if cross == 0.0 && &edge * &to_point >= 0.0 && &to_point * &to_point <= &edge * &edge {
+                return true;
+            }
+
+            if start.y <= point.y {
+                if end.y > point.y && cross > 0.0 {
+                    winding_number += 1;
+                }
+            } else if end.y <= point.y && cross < 0.0 {
+                winding_number -= 1;
+            }
+        }
+
+        winding_number != 0
+    }
+
+    /// Gives an iterator on all vertices of the cell
+    fn iter_vertices(&self) -> std::slice::Iter<'_, Point> {
+        self.vertices.iter()
+    }
+
+    /// Gives an iterator on all neighbors of the cell
+    fn iter_adjacencies(&self) -> std::slice::Iter<'_, Option<Neighbor>> {
+        self.adjacencies.iter()
+    }
+
+    /// Gives the centroid of the cell, computed with the shoelace formula
+    fn center(&self) -> Point {
+        let count = self.vertices.len();
+        let (mut x, mut y) = (0.0, 0.0);
+
+        for i in 0..count {
+            let (a, b) = (&self.vertices[i], &self.vertices[(i + 1) % count]);
+            let cross = a.x * b.y - b.x * a.y;
+            x += (a.x + b.x) * cross;
+            y += (a.y + b.y) * cross;
+        }
+
+        let area = self.signed_area();
+        build_point(x / (6.0 * area), y / (6.0 * area))
+    }
+
+    /// Gives the normals to each edge of the cell
+    fn normals(&self) -> Vec<Vector> {
+        self.edges()
+            .iter()
+            .map(|edge| edge.orthogonal_vector().normalize())
+            .collect()
+    }
+
+    /// Gives the siged area of the cell, computed with the shoelace formula.
+    /// Is positive if the vertices are defined counter-clockwise.
+    fn signed_area(&self) -> f64 {
+        let count = self.vertices.len();
+
+        0.5 * (0..count)
+            .map(|i| {
+                let (a, b) = (&self.vertices[i], &self.vertices[(i + 1) % count]);
+                a.x * b.y - b.x * a.y
+            })
+            .sum::<f64>()
+    }
+}
+
+impl Polygon {
+    pub fn edges(&self) -> Vec<Vector> {
+        let count = self.vertices.len();
+
+        (0..count)
+            .map(|i| self.vertices[i].segment_to(&self.vertices[(i + 1) % count]))
+            .collect()
+    }
+}
+
+/// Creates a new polygon
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let vertices = vec![
+///     build_point(0.0, 0.0),
+///     build_point(2.0, 0.0),
+///     build_point(2.0, 2.0),
+///     build_point(1.0, 1.0),
+///     build_point(0.0, 2.0),
+/// ];
+/// let polygon = build_polygon(vertices, vec![None; 5]);
+///
+/// assert_eq!(polygon.signed_area(), 3.0);
+/// assert!(polygon.include(&build_point(0.5, 1.0)));
+/// assert!(polygon.include(&build_point(1.0, 0.0)));
+/// assert!(!polygon.include(&build_point(1.0, 1.5)));
+/// ```
+pub fn build_polygon(vertices: Vec<Point>, adjacencies: Vec<Option<Neighbor>>) -> Polygon {
+    Polygon {
+        vertices,
+        adjacencies,
+    }
+}
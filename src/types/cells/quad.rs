@@ -0,0 +1,174 @@
+use crate::types::cells::cell_trait::*;
+use crate::types::cells::triangle::*;
+
+use crate::types::base::*;
+
+/// Definition of the quadrilateral cell.
+/// Vertices are counter-clockwise and the cell is expected to be convex. `adjacencies[i]` is the neighbor across the edge from `vertices[i]` to `vertices[i + 1]`.
+#[derive(Debug)]
+pub struct Quad {
+    pub vertices: [Point; 4],
+    pub adjacencies: [Option<Neighbor>; 4],
+}
+
+impl Copy for Quad {}
+
+impl Clone for Quad {
+    fn clone(&self) -> Quad {
+        *self
+    }
+}
+
+impl Cell for Quad {
+    /// Checks if the point is in this cell
+    fn include(&self, point: &Point) -> bool {
+        match self.bilinear_coordinates_from(point) {
+            None => false,
+            Some((s, t)) => (0.0..=1.0).contains(&s) & (0.0..=1.0).contains(&t),
+        }
+    }
+
+    /// Gives an iterator on all vertices of the cell
+    fn iter_vertices(&self) -> std::slice::Iter<'_, Point> {
+        self.vertices.iter()
+    }
+
+    /// Gives an iterator on all neighbors of the cell
+    fn iter_adjacencies(&self) -> std::slice::Iter<'_, Option<Neighbor>> {
+        self.adjacencies.iter()
+    }
+
+    /// Gives the centroid of the cell, weighted by the area of the two triangles splitting it
+    fn center(&self) -> Point {
+        let first = [self.vertices[0], self.vertices[1], self.vertices[2]];
+        let second = [self.vertices[0], self.vertices[2], self.vertices[3]];
+
+        let (first_area, second_area) = (
+            build_triangle(first, [None; 3]).signed_area(),
+            build_triangle(second, [None; 3]).signed_area(),
+        );
+        let first_center = &(&(&first[0] + &first[1]) + &first[2]) / 3.0;
+        let second_center = &(&(&second[0] + &second[1]) + &second[2]) / 3.0;
+
+        &(&(&first_center * first_area) + &(&second_center * second_area))
+            / (first_area + second_area)
+    }
+
+    /// Gives the normals to each edge of the cell
+    fn normals(&self) -> Vec<Vector> {
+        self.edges()
+            .iter()
+            .map(|edge| edge.orthogonal_vector().normalize())
+            .collect()
+    }
+
+    /// Gives the siged area of the cell.
+    /// Is positive if the vertices are defined counter-clockwise.
+    fn signed_area(&self) -> f64 {
+        0.5 * (0..4)
+            .map(|i| {
+                let (a, b) = (&self.vertices[i], &self.vertices[(i + 1) % 4]);
+                a.x * b.y - b.x * a.y
+            })
+            .sum::<f64>()
+    }
+}
+
+impl Quad {
+    pub fn edges(&self) -> [Vector; 4] {
+        [
+            self.vertices[0].segment_to(&self.vertices[1]),
+            self.vertices[1].segment_to(&self.vertices[2]),
+            self.vertices[2].segment_to(&self.vertices[3]),
+            self.vertices[3].segment_to(&self.vertices[0]),
+        ]
+    }
+
+    /// Computes the bilinear coordinates (s, t) of a point, inverse of the mapping
+    /// `(1 - s)(1 - t) v0 + s (1 - t) v1 + s t v2 + (1 - s) t v3`.
+    ///
+    /// Returns `None` if the mapping cannot be inverted at this point.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use meshing::*;
+    ///
+    /// let quad = build_quad(
+    ///     [
+    ///         build_point(0.0, 0.0),
+    ///         build_point(2.0, 0.0),
+    ///         build_point(3.0, 2.0),
+    ///         build_point(0.0, 1.0),
+    ///     ],
+    ///     [None; 4],
+    /// );
+    ///
+    /// let (s, t) = quad.bilinear_coordinates_from(&build_point(1.25, 0.75)).unwrap();
+    ///
+    /// assert!((s - 0.5).abs() < 1e-12);
+    /// assert!((t - 0.5).abs() < 1e-12);
+    /// assert!(quad.include(&build_point(1.25, 0.75)));
+    /// assert!(!quad.include(&build_point(2.5, 0.5)));
+    /// ```
+    pub fn bilinear_coordinates_from(&self, point: &Point) -> Option<(f64, f64)> {
+        let cross = |a: &Vector, b: &Vector| a.x * b.y - a.y * b.x;
+
+        let e = self.vertices[0].segment_to(&self.vertices[1]);
+        let f = self.vertices[0].segment_to(&self.vertices[3]);
+        let g = &self.vertices[1].segment_to(&self.vertices[0])
+            + &self.vertices[3].segment_to(&self.vertices[2]);
+        let h = self.vertices[0].segment_to(point);
+
+        let k2 = cross(&g, &f);
+        let k1 = cross(&e, &f) + cross(&h, &g);
+        let k0 = cross(&h, &e);
+
+        // s is found from t with the most reliable component
+        let s_from = |t: f64| {
+            let (x, y) = (e.x + g.x * t, e.y + g.y * t);
+            if x.abs() > y.abs() {
+                (h.x - f.x * t) / x
+            } else {
+                (h.y - f.y * t) / y
+            }
+        };
+
+        // Parallel opposite edges make the equation linear
+        if k2.abs() <= 1e-12 * k1.abs() {
+            if k1 == 0.0 {
+                return None;
+            }
+            let t = -k0 / k1;
+            return Some((s_from(t), t));
+        }
+
+        let discriminant = k1 * k1 - 4.0 * k0 * k2;
+        if discriminant < 0.0 {
+            return None;
+        }
+
+        let roots = [
+            (-k1 - discriminant.sqrt()) / (2.0 * k2),
+            (-k1 + discriminant.sqrt()) / (2.0 * k2),
+        ];
+        let distance_to_unit = |value: f64| (value.max(0.0) - value.min(1.0)).max(0.0);
+
+        // The root closest to the unit square is the one inside the cell
+        roots
+            .iter()
+            .map(|&t| (s_from(t), t))
+            .min_by(|(s1, t1), (s2, t2)| {
+                (distance_to_unit(*s1) + distance_to_unit(*t1))
+                    .total_cmp(&(distance_to_unit(*s2) + distance_to_unit(*t2)))
+            })
+    }
+}
+
+/// Creates a new quadrilateral
+pub fn build_quad(vertices: [Point; 4], adjacencies: [Option<Neighbor>; 4]) -> Quad {
+    Quad {
+        vertices,
+        adjacencies,
+    }
+}
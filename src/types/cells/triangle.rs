@@ -1,11 +1,9 @@
-//used to draw the triangles (test purpose)
-use flo_canvas::*;
-
 use crate::types::cells::cell_trait::*;
 
 use crate::types::base::*;
 
 /// Definition of the cell
+#[derive(Debug)]
 pub struct Triangle {
     pub vertices: [Point; 3],
     pub adjacencies: [Option<Neighbor>; 3],
@@ -121,39 +119,6 @@ impl Triangle {
         (a, b, c)
     }
 
-    /// Draw the triangle using flo_canvas, for debug purpose
-    pub fn draw(
-        &self,
-        window_dimension: &(Point, Point),
-        canvas: &DrawingTarget,
-        line_color: &Color,
-    ) {
-        canvas.draw(|gc| {
-            // Set up the canvas
-            gc.canvas_height((window_dimension.1.x - window_dimension.0.x) as f32);
-            gc.center_region(
-                window_dimension.0.x as f32,
-                window_dimension.0.y as f32,
-                window_dimension.1.x as f32,
-                window_dimension.1.y as f32,
-            );
-
-            // Draw a circle
-            gc.new_path();
-            gc.move_to(self.vertices[0].x as f32, self.vertices[0].y as f32);
-            gc.line_to(self.vertices[1].x as f32, self.vertices[1].y as f32);
-            gc.line_to(self.vertices[2].x as f32, self.vertices[2].y as f32);
-            gc.line_to(self.vertices[0].x as f32, self.vertices[0].y as f32);
-
-            // gc.fill_color(Color::Rgba(0.3, 0.6, 0.8, 0.0));
-            // gc.fill();
-            let width_factor: f32 = 0.001;
-            gc.line_width(width_factor * (window_dimension.1.x - window_dimension.0.x) as f32);
-            gc.stroke_color(*line_color);
-            gc.stroke();
-        });
-    }
-
     /// Debug function, prints vertices and adjacencies of a triangle
     pub fn print_triangle(&self) {
         println!(
@@ -180,18 +145,38 @@ impl Triangle {
         radius
     }
 
+    /// Computes the center of the circumcircle of the triangle
+    pub fn circumcenter(&self) -> Point {
+        let [a, b, c] = &self.vertices;
+        let d = 2.0 * (a.x * (b.y - c.y) + b.x * (c.y - a.y) + c.x * (a.y - b.y));
+        let (a2, b2, c2) = (
+            a.x * a.x + a.y * a.y,
+            b.x * b.x + b.y * b.y,
+            c.x * c.x + c.y * c.y,
+        );
+
+        build_point(
+            (a2 * (b.y - c.y) + b2 * (c.y - a.y) + c2 * (a.y - b.y)) / d,
+            (a2 * (c.x - b.x) + b2 * (a.x - c.x) + c2 * (b.x - a.x)) / d,
+        )
+    }
+
     /// Checks if a point is in the circumcircle of a triangle
     pub fn is_point_in_circumucircle(&self, point: &Point) -> bool {
+        self.circumcircle_determinant(point) > 0.0
+    }
+
+    /// Computes the determinant used by `is_point_in_circumucircle`.
+    /// It is positive if the point is inside the circumcircle and scales as a length to the power 4.
+    pub fn circumcircle_determinant(&self, point: &Point) -> f64 {
         let pa = point.segment_to(&self.vertices[0]);
         let pb = point.segment_to(&self.vertices[1]);
         let pc = point.segment_to(&self.vertices[2]);
 
         //see the circumcircle wikipedia page, this is the determinant of a matrix which will tell if the point is inside or outside of the circumcircle
         // or https://stackoverflow.com/questions/39984709/how-can-i-check-wether-a-point-is-inside-the-circumcircle-of-3-points
-        let det = &pa * &pa * (pb.x * pc.y - pc.x * pb.y) - &pb * &pb * (pa.x * pc.y - pc.x * pa.y)
-            + &pc * &pc * (pa.x * pb.y - pb.x * pa.y);
-
-        det > 0.0
+        &pa * &pa * (pb.x * pc.y - pc.x * pb.y) - &pb * &pb * (pa.x * pc.y - pc.x * pa.y)
+            + &pc * &pc * (pa.x * pb.y - pb.x * pa.y)
     }
 
     /// Finds if the point is one of the triangle's vertices, and returns it index if so. Beware of floating point approwimations.
@@ -0,0 +1,232 @@
+//! Checks that a triangulation is consistent, to catch a bad edge flip before it shows up as a panic or a wrong picture.
+//!
+//! Nothing here panics: every problem found is reported as a [`MeshIssue`].
+
+use std::collections::{HashMap, HashSet};
+
+use crate::types::*;
+
+/// Problem found in a mesh by [`validate`].
+///
+/// Cells are given by their index in the mesh and edges by their local index,
+/// edge `i` going from `vertices[i]` to `vertices[(i + 1) % 3]`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MeshIssue {
+    /// The cell uses the same point for two of its vertices.
+    DegenerateCell { cell: usize },
+    /// The cell is flat or clockwise.
+    NonPositiveArea { cell: usize, area: f64 },
+    /// The neighbor index is not in the mesh.
+    DanglingNeighbor {
+        cell: usize,
+        edge: usize,
+        neighbor: usize,
+    },
+    /// The neighbor does not share the edge, or does not point back to the cell.
+    AsymmetricAdjacency {
+        cell: usize,
+        edge: usize,
+        neighbor: usize,
+    },
+    /// The vertex opposite to the edge in the neighbor lies in the circumcircle of the cell.
+    NotDelaunay { cell: usize, edge: usize },
+    /// Two vertices of the input list are at the same position.
+    DuplicateVertex { first: usize, second: usize },
+    /// The vertex of the input list is not used by any cell.
+    UnusedVertex { vertex: usize },
+    /// The boundary edges do not form closed loops at this point.
+    OpenBoundary { point: Point },
+}
+
+/// Relative tolerance on the circumcircle determinant, so that cocircular points (e.g. on a grid) are not reported.
+pub(crate) const DELAUNAY_TOLERANCE: f64 = 1e-10;
+
+/// Key used to compare points exactly in hash maps (`-0.0` and `0.0` are the same point).
+pub(crate) fn point_key(point: &Point) -> (u64, u64) {
+    ((point.x + 0.0).to_bits(), (point.y + 0.0).to_bits())
+}
+
+/// Keys of the vertices on the boundary, both ends of an edge without neighbor cell being on it.
+pub(crate) fn boundary_vertices(triangles: &[Triangle]) -> HashSet<(u64, u64)> {
+    let mut vertices = HashSet::new();
+    for triangle in triangles {
+        for edge in 0..3 {
+            if !matches!(triangle.adjacencies[edge], Some(Neighbor::Cell(_))) {
+                vertices.insert(point_key(&triangle.vertices[edge]));
+                vertices.insert(point_key(&triangle.vertices[(edge + 1) % 3]));
+            }
+        }
+    }
+    vertices
+}
+
+/// Key used to compare edges exactly in hash maps, whatever their direction.
+pub(crate) fn edge_key(a: &Point, b: &Point) -> ((u64, u64), (u64, u64)) {
+    let (a, b) = (point_key(a), point_key(b));
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// Checks the triangulation and returns every issue found. An empty result means the mesh is valid.
+///
+/// The following properties are checked:
+/// - adjacencies are symmetric and neighbors share the corresponding edge,
+/// - every cell is counter-clockwise (`signed_area` > 0),
+/// - each interior edge not listed in `constrained_edges` is locally Delaunay,
+/// - `vertices` has no duplicates and each of them is used by a cell,
+/// - the boundary edges (`None` or `Neighbor::Boundary`) form closed loops.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let (a, b, c, d) = (
+///     build_point(0.0, 0.0),
+///     build_point(1.0, 0.0),
+///     build_point(1.0, 1.0),
+///     build_point(0.0, 1.0),
+/// );
+/// let mesh = vec![
+///     build_triangle([a, b, c], [None, None, Some(Neighbor::Cell(1))]),
+///     build_triangle([a, c, d], [Some(Neighbor::Cell(0)), None, None]),
+/// ];
+///
+/// assert!(validate(&[a, b, c, d], &mesh, &[]).is_empty());
+///
+/// let mut broken = mesh.clone();
+/// broken[1].adjacencies[0] = None;
+/// let issues = validate(&[a, b, c, d], &broken, &[]);
+///
+/// assert!(issues.contains(&MeshIssue::AsymmetricAdjacency { cell: 0, edge: 2, neighbor: 1 }));
+/// assert!(issues.contains(&MeshIssue::OpenBoundary { point: a }));
+/// ```
+pub fn validate(
+    vertices: &[Point],
+    triangles: &[Triangle],
+    constrained_edges: &[(Point, Point)],
+) -> Vec<MeshIssue> {
+    let mut issues = Vec::new();
+
+    let is_constrained = |a: &Point, b: &Point| {
+        constrained_edges
+            .iter()
+            .any(|(p, q)| (p == a && q == b) || (p == b && q == a))
+    };
+
+    // Balance of outgoing minus incoming boundary edges at each point
+    let mut boundary_balance: HashMap<(u64, u64), (Point, i32)> = HashMap::new();
+    let mut used_points = HashSet::new();
+
+    for (cell, triangle) in triangles.iter().enumerate() {
+        let v = &triangle.vertices;
+
+        if v[0] == v[1] || v[1] == v[2] || v[2] == v[0] {
+            issues.push(MeshIssue::DegenerateCell { cell });
+        } else {
+            let area = triangle.signed_area();
+            if area <= 0.0 {
+                issues.push(MeshIssue::NonPositiveArea { cell, area });
+            }
+        }
+
+        for point in v {
+            used_points.insert(point_key(point));
+        }
+
+        for edge in 0..3 {
+            let (start, end) = (&v[edge], &v[(edge + 1) % 3]);
+
+            let neighbor = match triangle.adjacencies[edge] {
+                None | Some(Neighbor::Boundary) => {
+                    boundary_balance
+                        .entry(point_key(start))
+                        .or_insert((*start, 0))
+                        .1 += 1;
+                    boundary_balance
+                        .entry(point_key(end))
+                        .or_insert((*end, 0))
+                        .1 -= 1;
+                    continue;
+                }
+                Some(Neighbor::Cell(neighbor)) => neighbor,
+            };
+
+            if neighbor >= triangles.len() {
+                issues.push(MeshIssue::DanglingNeighbor {
+                    cell,
+                    edge,
+                    neighbor,
+                });
+                continue;
+            }
+
+            let other = &triangles[neighbor];
+            let shared_edge = (0..3)
+                .find(|&k| &other.vertices[k] == end && &other.vertices[(k + 1) % 3] == start);
+
+            let other_edge = match shared_edge {
+                Some(k)
+                    if neighbor != cell && other.adjacencies[k] == Some(Neighbor::Cell(cell)) =>
+                {
+                    k
+                }
+                _ => {
+                    issues.push(MeshIssue::AsymmetricAdjacency {
+                        cell,
+                        edge,
+                        neighbor,
+                    });
+                    continue;
+                }
+            };
+
+            // Each interior edge is only tested once
+            if neighbor > cell && !is_constrained(start, end) {
+                let opposite = &other.vertices[(other_edge + 2) % 3];
+                let scale = v
+                    .iter()
+                    .map(|vertex| opposite.segment_to(vertex).norm())
+                    .fold(0.0, f64::max)
+                    .powi(4);
+
+                if triangle.circumcircle_determinant(opposite) > DELAUNAY_TOLERANCE * scale {
+                    issues.push(MeshIssue::NotDelaunay { cell, edge });
+                }
+            }
+        }
+    }
+
+    let mut boundary_issues: Vec<Point> = boundary_balance
+        .values()
+        .filter(|(_, balance)| *balance != 0)
+        .map(|(point, _)| *point)
+        .collect();
+    boundary_issues.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    issues.extend(
+        boundary_issues
+            .into_iter()
+            .map(|point| MeshIssue::OpenBoundary { point }),
+    );
+
+    let mut first_occurrence: HashMap<(u64, u64), usize> = HashMap::new();
+    for (vertex, point) in vertices.iter().enumerate() {
+        match first_occurrence.get(&point_key(point)) {
+            Some(&first) => issues.push(MeshIssue::DuplicateVertex {
+                first,
+                second: vertex,
+            }),
+            None => {
+                first_occurrence.insert(point_key(point), vertex);
+            }
+        }
+        if !used_points.contains(&point_key(point)) {
+            issues.push(MeshIssue::UnusedVertex { vertex });
+        }
+    }
+
+    issues
+}
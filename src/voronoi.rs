@@ -0,0 +1,83 @@
+//! Dual of a Delaunay triangulation, as a mesh of polygons for vertex-centered finite volumes.
+
+use std::collections::HashSet;
+
+use crate::error::*;
+use crate::mixed_mesh::*;
+use crate::triangulation::*;
+use crate::types::*;
+use crate::validation::point_key;
+
+/// Builds the Voronoi diagram of the vertices of a Delaunay triangulation, as a mesh of polygons.
+///
+/// The cell of an interior vertex joins the circumcenters of the triangles around it.
+/// The cell of a boundary vertex is closed by the vertex itself and the middle of its two boundary edges,
+/// which is only valid if the circumcenters of the boundary triangles are inside the domain.
+///
+/// The i-th cell is the dual of the i-th vertex given by `unique_vertices`.
+///
+/// # Example
+///
+/// ```rust
+/// use meshing::*;
+///
+/// let vertices = vec![
+///     build_point(0.0, 0.0),
+///     build_point(2.0, 0.0),
+///     build_point(2.0, 2.0),
+///     build_point(0.0, 2.0),
+///     build_point(1.0, 1.0),
+/// ];
+/// let triangles = triangulate(&vertices).unwrap();
+/// let dual = voronoi_dual(&triangles).unwrap();
+///
+/// let area: f64 = dual.cells.iter().map(|cell| cell.signed_area()).sum();
+/// assert_eq!(dual.len(), 5);
+/// assert!((area - 4.0).abs() < 1e-12);
+/// ```
+pub fn voronoi_dual(triangles: &[Triangle]) -> Result<MixedMesh, MeshError> {
+    let circumcenters: Vec<Point> = triangles
+        .iter()
+        .map(|triangle| triangle.circumcenter())
+        .collect();
+
+    let mut seen = HashSet::new();
+    let mut cells = Vec::new();
+
+    for (id, triangle) in triangles.iter().enumerate() {
+        for vertex in &triangle.vertices {
+            if !seen.insert(point_key(vertex)) {
+                continue;
+            }
+
+            let star = vertex_star(vertex, triangles, id)?;
+            let mut polygon: Vec<Point> = Vec::new();
+
+            if !star.closed {
+                let first = &triangles[star.cells[0]];
+                let local_id = first.find_point_in_triangle_vertices(vertex).unwrap_or(0);
+                polygon.push(*vertex);
+                polygon.push(&(vertex + &first.vertices[(local_id + 1) % 3]) / 2.0);
+            }
+
+            polygon.extend(star.cells.iter().map(|cell| circumcenters[*cell]));
+
+            if !star.closed {
+                let last = &triangles[star.cells[star.cells.len() - 1]];
+                let local_id = last.find_point_in_triangle_vertices(vertex).unwrap_or(0);
+                polygon.push(&(&last.vertices[(local_id + 2) % 3] + vertex) / 2.0);
+            }
+
+            // Cocircular vertices give the same circumcenter to neighboring triangles
+            polygon.dedup();
+            if polygon.len() > 1 && polygon[0] == polygon[polygon.len() - 1] {
+                polygon.pop();
+            }
+
+            let count = polygon.len();
+            cells.push(build_polygon(polygon, vec![None; count]).into());
+        }
+    }
+
+    Ok(MixedMesh::from_cells(cells))
+}